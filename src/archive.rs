@@ -0,0 +1,184 @@
+// Minimal archive writers for generated bundles: ZIP with stored (uncompressed) entries and
+// POSIX ustar. Timestamps are fixed so the same input always yields the same bytes.
+
+// 1980-01-01 00:00, the earliest date a ZIP entry can carry
+const DOS_DATE: u16 = (1 << 5) | 1;
+const DOS_TIME: u16 = 0;
+
+pub fn zip(files: &[(String, String)]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut central = Vec::new();
+
+    for (name, content) in files {
+        let data = content.as_bytes();
+        let crc = crc32(data);
+        let offset = out.len() as u32;
+
+        // Local file header
+        put_u32(&mut out, 0x0403_4b50);
+        put_u16(&mut out, 20); // version needed
+        put_u16(&mut out, 0x0800); // UTF-8 names
+        put_u16(&mut out, 0); // stored
+        put_u16(&mut out, DOS_TIME);
+        put_u16(&mut out, DOS_DATE);
+        put_u32(&mut out, crc);
+        put_u32(&mut out, data.len() as u32);
+        put_u32(&mut out, data.len() as u32);
+        put_u16(&mut out, name.len() as u16);
+        put_u16(&mut out, 0); // extra length
+        out.extend_from_slice(name.as_bytes());
+        out.extend_from_slice(data);
+
+        // Central directory entry
+        put_u32(&mut central, 0x0201_4b50);
+        put_u16(&mut central, (3 << 8) | 20); // made by: Unix
+        put_u16(&mut central, 20);
+        put_u16(&mut central, 0x0800);
+        put_u16(&mut central, 0);
+        put_u16(&mut central, DOS_TIME);
+        put_u16(&mut central, DOS_DATE);
+        put_u32(&mut central, crc);
+        put_u32(&mut central, data.len() as u32);
+        put_u32(&mut central, data.len() as u32);
+        put_u16(&mut central, name.len() as u16);
+        put_u16(&mut central, 0); // extra length
+        put_u16(&mut central, 0); // comment length
+        put_u16(&mut central, 0); // disk number
+        put_u16(&mut central, 0); // internal attributes
+        put_u32(&mut central, 0o100644 << 16); // regular file, rw-r--r--
+        put_u32(&mut central, offset);
+        central.extend_from_slice(name.as_bytes());
+    }
+
+    let central_offset = out.len() as u32;
+    out.extend_from_slice(&central);
+
+    // End of central directory
+    put_u32(&mut out, 0x0605_4b50);
+    put_u16(&mut out, 0);
+    put_u16(&mut out, 0);
+    put_u16(&mut out, files.len() as u16);
+    put_u16(&mut out, files.len() as u16);
+    put_u32(&mut out, central.len() as u32);
+    put_u32(&mut out, central_offset);
+    put_u16(&mut out, 0);
+    out
+}
+
+// Names must fit the 100-byte ustar name field.
+pub fn tar(files: &[(String, String)]) -> Vec<u8> {
+    let mut out = Vec::new();
+
+    for (name, content) in files {
+        let data = content.as_bytes();
+        let mut header = [0u8; 512];
+        header[..name.len()].copy_from_slice(name.as_bytes());
+        header[100..108].copy_from_slice(b"0000644\0");
+        header[108..116].copy_from_slice(b"0000000\0");
+        header[116..124].copy_from_slice(b"0000000\0");
+        header[124..136].copy_from_slice(format!("{:011o}\0", data.len()).as_bytes());
+        header[136..148].copy_from_slice(b"00000000000\0");
+        header[148..156].copy_from_slice(b"        "); // checksum is computed over spaces
+        header[156] = b'0';
+        header[257..263].copy_from_slice(b"ustar\0");
+        header[263..265].copy_from_slice(b"00");
+        let checksum: u32 = header.iter().map(|&b| b as u32).sum();
+        header[148..156].copy_from_slice(format!("{:06o}\0 ", checksum).as_bytes());
+
+        out.extend_from_slice(&header);
+        out.extend_from_slice(data);
+        out.resize(out.len() + (512 - data.len() % 512) % 512, 0);
+    }
+
+    // Two empty blocks mark the end of the archive
+    out.resize(out.len() + 1024, 0);
+    out
+}
+
+// CRC-32 (IEEE 802.3, reflected), bitwise; bundles are small enough not to need a table.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+fn put_u16(out: &mut Vec<u8>, v: u16) {
+    out.extend_from_slice(&v.to_le_bytes());
+}
+
+fn put_u32(out: &mut Vec<u8>, v: u32) {
+    out.extend_from_slice(&v.to_le_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u16_at(bytes: &[u8], at: usize) -> u16 {
+        u16::from_le_bytes([bytes[at], bytes[at + 1]])
+    }
+
+    fn u32_at(bytes: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+    }
+
+    fn files() -> Vec<(String, String)> {
+        vec![
+            ("app/a.txt".to_string(), "123456789".to_string()),
+            ("app/b.txt".to_string(), String::new()),
+        ]
+    }
+
+    #[test]
+    fn crc32_matches_the_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn zip_headers_point_at_each_other() {
+        let out = zip(&files());
+        let eocd = out.len() - 22;
+        assert_eq!(u32_at(&out, eocd), 0x0605_4b50);
+        assert_eq!(u16_at(&out, eocd + 10), 2);
+        let central = u32_at(&out, eocd + 16) as usize;
+        assert_eq!(central + u32_at(&out, eocd + 12) as usize, eocd);
+
+        // The first central entry describes the first local header
+        assert_eq!(u32_at(&out, central), 0x0201_4b50);
+        assert_eq!(u32_at(&out, central + 16), 0xCBF4_3926);
+        assert_eq!(u32_at(&out, central + 24), 9);
+        assert_eq!(&out[central + 46..central + 55], b"app/a.txt");
+        let local = u32_at(&out, central + 42) as usize;
+        assert_eq!(u32_at(&out, local), 0x0403_4b50);
+        assert_eq!(u32_at(&out, local + 14), 0xCBF4_3926);
+        assert_eq!(u32_at(&out, local + 18), 9);
+        assert_eq!(u16_at(&out, local + 26), 9);
+        assert_eq!(&out[local + 30..local + 39], b"app/a.txt");
+        assert_eq!(&out[local + 39..local + 48], b"123456789");
+    }
+
+    #[test]
+    fn tar_headers_carry_valid_checksums() {
+        let out = tar(&files());
+        // Two headers, one padded data block and the two end blocks
+        assert_eq!(out.len(), 512 * 5);
+        for at in [0, 1024] {
+            let mut header = out[at..at + 512].to_vec();
+            let stored = std::str::from_utf8(&header[148..154]).unwrap();
+            let stored = u32::from_str_radix(stored, 8).unwrap();
+            header[148..156].copy_from_slice(b"        ");
+            assert_eq!(stored, header.iter().map(|&b| b as u32).sum::<u32>());
+            assert_eq!(&header[257..263], b"ustar\0");
+        }
+        assert_eq!(&out[124..135], b"00000000011");
+        assert_eq!(&out[512..521], b"123456789");
+        assert!(out[1536..].iter().all(|&b| b == 0));
+    }
+}
@@ -0,0 +1,214 @@
+use crate::error::ToolError;
+use crate::i18n::Message;
+use crate::tools::Group;
+use sha2::{Digest, Sha256};
+
+// Pages that stay reachable without a key. The docs page is a static shell; the spec it
+// loads from /api/openapi.json is still protected.
+const PUBLIC_PATHS: &[&str] = &["/", "/api/ping", "/api/docs"];
+
+// What a caller may do: everything, or only the tools of some groups.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Access {
+    #[default]
+    All,
+    Groups(Vec<Group>),
+}
+
+impl Access {
+    pub fn allows(&self, group: Group) -> bool {
+        match self {
+            Access::All => true,
+            Access::Groups(groups) => groups.contains(&group),
+        }
+    }
+
+    pub fn check(&self, group: Group) -> Result<(), ToolError> {
+        if self.allows(group) {
+            return Ok(());
+        }
+        Err(ToolError::Forbidden(Message::new(
+            "auth.forbidden_group",
+            &[&group.as_str()],
+        )))
+    }
+}
+
+// API keys from the API_KEYS secret: entries separated by `;` or newlines, each `key` (all
+// tools) or `key:group,group` with the group names from the OpenAPI tags, e.g.
+// `k1; k2:generators,text`. An empty secret leaves the API open.
+pub struct ApiKeys {
+    keys: Vec<(String, Access)>,
+}
+
+impl ApiKeys {
+    pub fn parse(secret: &str) -> Self {
+        let keys = secret
+            .split([';', '\n'])
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| match entry.split_once(':') {
+                Some((key, scopes)) => (key.trim().to_string(), parse_scopes(scopes)),
+                None => (entry.to_string(), Access::All),
+            })
+            // `:text` has no key to present
+            .filter(|(key, _)| !key.is_empty())
+            .collect();
+        ApiKeys { keys }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.keys.is_empty()
+    }
+
+    // `authorization` is the Authorization header (`Bearer <key>`), `api_key` the
+    // X-API-Key header; either may carry the key.
+    pub fn authenticate(
+        &self,
+        authorization: Option<&str>,
+        api_key: Option<&str>,
+    ) -> Result<Access, ToolError> {
+        if !self.is_enabled() {
+            return Ok(Access::All);
+        }
+        let bearer = authorization.and_then(|h| {
+            let (scheme, token) = h.trim().split_once(' ')?;
+            if scheme.eq_ignore_ascii_case("bearer") {
+                Some(token.trim())
+            } else {
+                None
+            }
+        });
+        let presented = match api_key.map(str::trim).or(bearer) {
+            Some(key) if !key.is_empty() => key,
+            _ => {
+                return Err(ToolError::Unauthorized(Message::new(
+                    "auth.missing_key",
+                    &[],
+                )))
+            }
+        };
+        // Compare against every key so the timing does not reveal which one matched
+        let mut matched = None;
+        for (key, access) in &self.keys {
+            if constant_time_eq(key.as_bytes(), presented.as_bytes()) {
+                matched = Some(access);
+            }
+        }
+        matched
+            .cloned()
+            .ok_or_else(|| ToolError::Unauthorized(Message::new("auth.invalid_key", &[])))
+    }
+}
+
+// Share links open the homepage, which then asks for the key like any other page load.
+pub fn is_public(path: &str) -> bool {
+    PUBLIC_PATHS.contains(&path) || path.starts_with("/s/")
+}
+
+// `*` grants everything; unknown group names are ignored rather than widening access.
+fn parse_scopes(scopes: &str) -> Access {
+    let names: Vec<&str> = scopes.split(',').map(str::trim).collect();
+    if names.contains(&"*") {
+        return Access::All;
+    }
+    Access::Groups(
+        Group::ALL
+            .iter()
+            .copied()
+            .filter(|g| names.contains(&g.as_str()))
+            .collect(),
+    )
+}
+
+// Compares digests, which always have the same length, so neither the length of a key nor
+// the position of the first difference shows in the timing.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    let (a, b) = (Sha256::digest(a), Sha256::digest(b));
+    a.iter()
+        .zip(b.iter())
+        .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+        == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys() -> ApiKeys {
+        ApiKeys::parse(" all ;\n text-only : text, nope ; :crypto ;; none: nope ; star:*,text")
+    }
+
+    #[test]
+    fn parse_skips_blank_and_keyless_entries() {
+        let keys = keys().keys;
+        let names: Vec<&str> = keys.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(names, ["all", "text-only", "none", "star"]);
+        assert_eq!(keys[0].1, Access::All);
+        assert_eq!(keys[1].1, Access::Groups(vec![Group::Text]));
+        assert_eq!(keys[2].1, Access::Groups(Vec::new()));
+        assert_eq!(keys[3].1, Access::All);
+        assert!(!ApiKeys::parse(" ; \n ").is_enabled());
+    }
+
+    #[test]
+    fn parse_scopes_ignores_unknown_groups() {
+        assert_eq!(
+            parse_scopes("generators, text ,crypt"),
+            Access::Groups(vec![Group::Generators, Group::Text])
+        );
+        assert_eq!(parse_scopes("text,*"), Access::All);
+        assert_eq!(parse_scopes(""), Access::Groups(Vec::new()));
+    }
+
+    #[test]
+    fn authenticate_checks_the_presented_key() {
+        let keys = keys();
+        let status = |auth, api_key| keys.authenticate(auth, api_key).unwrap_err().status();
+        assert_eq!(status(None, None), 401);
+        assert_eq!(status(Some("Bearer "), Some(" ")), 401);
+        assert_eq!(status(Some("Bearer wrong"), None), 401);
+        assert_eq!(status(Some("Basic all"), None), 401);
+        assert_eq!(status(None, Some("al")), 401);
+        assert_eq!(status(None, Some("all2")), 401);
+
+        assert_eq!(
+            keys.authenticate(Some("bearer  all "), None).unwrap(),
+            Access::All
+        );
+        // X-API-Key wins over the Authorization header
+        let access = keys
+            .authenticate(Some("Bearer all"), Some("text-only"))
+            .unwrap();
+        assert!(access.allows(Group::Text));
+        assert_eq!(access.check(Group::Crypto).unwrap_err().status(), 403);
+
+        let open = ApiKeys::parse("");
+        assert_eq!(open.authenticate(None, None).unwrap(), Access::All);
+    }
+
+    #[test]
+    fn only_the_listed_pages_are_public() {
+        for path in ["/", "/api/ping", "/api/docs", "/s/abc123"] {
+            assert!(is_public(path), "{}", path);
+        }
+        for path in [
+            "/api/openapi.json",
+            "/api/md5",
+            "/api/docs/x",
+            "/s",
+            "/share/abc",
+        ] {
+            assert!(!is_public(path), "{}", path);
+        }
+    }
+
+    #[test]
+    fn constant_time_eq_compares_whole_values() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(constant_time_eq(b"", b""));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret2"));
+        assert!(!constant_time_eq(b"", b"secret"));
+    }
+}
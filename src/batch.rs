@@ -0,0 +1,139 @@
+use crate::error::{ErrorBody, ToolError};
+use crate::tools::{self, Context, Input};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::future::Future;
+
+#[derive(Deserialize, JsonSchema)]
+pub struct BatchItem {
+    // Tool name as in `/api/<tool>`
+    pub tool: String,
+    // Request fields of the tool; omit for its defaults
+    #[serde(default)]
+    pub input: Map<String, Value>,
+}
+
+// One entry per request item, in the same order; exactly one of `result` / `error` is set.
+#[derive(Serialize, JsonSchema)]
+pub struct BatchItemResult {
+    pub tool: String,
+    pub status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorBody>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct BatchResponse {
+    pub results: Vec<BatchItemResult>,
+}
+
+// Runs every item through the registry. A failing item does not abort the batch; only an
+// oversized batch is rejected as a whole. `charge` takes a token from a tool's rate limit
+// before each call, so a batch cannot call a tool more often than `/api/<tool>` can.
+pub async fn run<F, Fut>(
+    items: Vec<BatchItem>,
+    ctx: &Context,
+    mut charge: F,
+) -> Result<BatchResponse, ToolError>
+where
+    F: FnMut(&'static str) -> Fut,
+    Fut: Future<Output = Result<(), ToolError>>,
+{
+    ctx.limits.batch_items(items.len())?;
+
+    let mut results = Vec::new();
+    for item in items {
+        let outcome = match tools::find(&item.tool) {
+            Some(tool) => match charge(tool.name()).await {
+                Ok(()) => tool.call(Input::Json(Value::Object(item.input)), ctx),
+                Err(e) => Err(e),
+            },
+            None => Err(ToolError::unknown_tool(&item.tool)),
+        };
+        results.push(match outcome {
+            Ok(result) => BatchItemResult {
+                tool: item.tool,
+                status: 200,
+                result: Some(result),
+                error: None,
+            },
+            Err(e) => BatchItemResult {
+                tool: item.tool,
+                status: e.status(),
+                result: None,
+                error: Some(e.envelope(ctx.locale).error),
+            },
+        });
+    }
+
+    Ok(BatchResponse { results })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ratelimit::testing::{block_on, charge};
+
+    fn item(tool: &str, input: Value) -> BatchItem {
+        BatchItem {
+            tool: tool.to_string(),
+            input: input.as_object().cloned().unwrap_or_default(),
+        }
+    }
+
+    #[test]
+    fn oversized_batches_are_rejected_whole() {
+        let mut ctx = Context::default();
+        ctx.limits.max_batch_items = 2;
+        let items = || (0..3).map(|_| item("md5", Value::Null)).collect();
+        let e = block_on(run(items(), &ctx, charge(10))).err().unwrap();
+        assert_eq!(e.status(), 413);
+        ctx.limits.max_batch_items = 3;
+        let results = block_on(run(items(), &ctx, charge(10))).unwrap().results;
+        assert_eq!(results.len(), 3);
+    }
+
+    #[cfg(feature = "crypto")]
+    #[test]
+    fn failing_items_do_not_affect_the_others() {
+        let ctx = Context::default();
+        let items = vec![
+            item("md5", serde_json::json!({ "text": "abc" })),
+            item("no-such-tool", Value::Null),
+            item(
+                "base64",
+                serde_json::json!({ "text": "%%%", "action": "decode" }),
+            ),
+            item("md5", serde_json::json!({ "text": "" })),
+        ];
+        let results = block_on(run(items, &ctx, charge(10))).unwrap().results;
+        let statuses: Vec<u16> = results.iter().map(|r| r.status).collect();
+        assert_eq!(statuses, [200, 404, 422, 200]);
+        assert_eq!(
+            results[0].result.as_ref().unwrap()["md5_32_lower"],
+            "900150983cd24fb0d6963f7d28e17f72"
+        );
+        assert_eq!(results[1].error.as_ref().unwrap().code, "not_found");
+        assert!(results[2].result.is_none());
+        assert!(results[3].error.is_none());
+    }
+
+    #[cfg(feature = "crypto")]
+    #[test]
+    fn items_over_the_tool_budget_are_rate_limited() {
+        let ctx = Context::default();
+        let items = vec![
+            item("md5", serde_json::json!({ "text": "a" })),
+            item("md5", serde_json::json!({ "text": "b" })),
+            item("base64", serde_json::json!({ "text": "c" })),
+            item("md5", serde_json::json!({ "text": "d" })),
+        ];
+        let results = block_on(run(items, &ctx, charge(2))).unwrap().results;
+        let statuses: Vec<u16> = results.iter().map(|r| r.status).collect();
+        assert_eq!(statuses, [200, 200, 200, 429]);
+        assert_eq!(results[3].error.as_ref().unwrap().code, "rate_limited");
+    }
+}
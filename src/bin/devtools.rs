@@ -0,0 +1,5 @@
+// Native front end to the worker's tools, for offline use. See `devtools help`.
+fn main() {
+    let args = std::env::args().skip(1).collect();
+    std::process::exit(my_rust_worker::cli::run(args));
+}
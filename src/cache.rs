@@ -0,0 +1,108 @@
+use crate::health::GIT_SHA;
+use crate::i18n::Locale;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+// Seconds a pure tool's result stays in the edge cache, from CACHE_TTL; unset or 0 turns the
+// cache off. ETags are sent either way.
+pub fn ttl(value: Option<&str>) -> u64 {
+    value.and_then(|v| v.trim().parse().ok()).unwrap_or(0)
+}
+
+// Cache key of a tool call: the build, tool, locale and the request as the tool parsed it
+// (`DynTool::pure_request`), with object keys sorted so equivalent requests share an entry.
+pub fn key(tool: &str, locale: Locale, request: &Value) -> String {
+    digest(&[GIT_SHA, tool, locale.as_str(), &sorted(request).to_string()])
+}
+
+// Strong validator of a rendered response. The content type is part of it, since `format`
+// picks a different representation of the same result.
+pub fn etag(content_type: &str, body: &str) -> String {
+    format!("\"{}\"", &digest(&[content_type, body])[..32])
+}
+
+// If-None-Match uses the weak comparison: `W/` prefixes are ignored and `*` matches anything.
+pub fn matches(if_none_match: &str, etag: &str) -> bool {
+    if_none_match
+        .split(',')
+        .map(str::trim)
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+}
+
+fn sorted(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k.clone(), sorted(v)))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.iter().map(sorted).collect()),
+        other => other.clone(),
+    }
+}
+
+fn digest(parts: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    hex::encode(hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "crypto")]
+    #[test]
+    fn equivalent_requests_share_a_key() {
+        use crate::limits::Limits;
+        use crate::tools::{self, Input};
+
+        let tool = tools::find("base64").unwrap();
+        let key_of = |input: Input| {
+            let request = tool.pure_request(&input, &Limits::default()).unwrap();
+            key(tool.name(), Locale::En, &request)
+        };
+        let encode = key_of(Input::Query("text=a%20b".to_string()));
+        assert_eq!(
+            encode,
+            key_of(Input::Body(r#"{"text": "a b"}"#.to_string()))
+        );
+        assert_eq!(
+            encode,
+            key_of(Input::Body(
+                r#"{"action": "encode", "text": "a b"}"#.to_string()
+            ))
+        );
+        assert_ne!(
+            encode,
+            key_of(Input::Body(
+                r#"{"action": "decode", "text": "a b"}"#.to_string()
+            ))
+        );
+        let request = tool
+            .pure_request(&Input::Query("text=a".to_string()), &Limits::default())
+            .unwrap();
+        assert_ne!(
+            key(tool.name(), Locale::En, &request),
+            key(tool.name(), Locale::ZhCn, &request)
+        );
+    }
+
+    #[test]
+    fn if_none_match_uses_the_weak_comparison() {
+        let etag = etag("application/json", "{}");
+        assert!(matches(&etag, &etag));
+        assert!(matches(&format!("\"x\", W/{}", etag), &etag));
+        assert!(matches("*", &etag));
+        assert!(!matches("\"x\"", &etag));
+        assert_ne!(etag, super::etag("text/plain", "{}"));
+    }
+}
@@ -0,0 +1,299 @@
+use crate::i18n::Locale;
+use crate::openapi;
+use crate::output::{self, Format};
+use crate::tools::{self, Context, DynTool, Input};
+use serde_json::Value;
+use std::env;
+use std::io::{self, Read, Write};
+
+const USAGE: &str = "\
+Usage: devtools <tool> [VALUE] [--<field> <value>]... [-o json|yaml|toml|text|raw] [--lang en|zh-CN]
+       devtools <tool> --json '<request>'   (or --json - to read the request from stdin)
+       devtools list
+       devtools help [tool]
+
+VALUE fills the tool's main field; `-` reads it from stdin.";
+
+// Runs one command line (without the program name) and returns the process exit code:
+// 0 on success, 1 when the tool rejects its input, 2 for a bad command line.
+pub fn run(args: Vec<String>) -> i32 {
+    let text = match args.first().map(String::as_str) {
+        None => return usage_error("missing command"),
+        Some("help") | Some("-h") | Some("--help") if args.len() < 2 => USAGE.to_string(),
+        Some("help") => match tools::find(&args[1]) {
+            Some(tool) => tool_help(tool),
+            None => return usage_error(&format!("unknown tool '{}'", args[1])),
+        },
+        Some("list") => tools::all()
+            .iter()
+            .map(|t| {
+                format!(
+                    "{:<16} {:<10} {}",
+                    t.name(),
+                    t.group().as_str(),
+                    t.description(env_locale())
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Some(name) => match tools::find(name) {
+            Some(tool) => return call(tool, &args[1..]),
+            None => return usage_error(&format!("unknown tool '{}'; try `devtools list`", name)),
+        },
+    };
+    print(text);
+    0
+}
+
+struct Invocation {
+    input: Input,
+    format: Option<Format>,
+    locale: Locale,
+}
+
+fn call(tool: &dyn DynTool, args: &[String]) -> i32 {
+    let invocation = match parse(tool, args) {
+        Ok(invocation) => invocation,
+        Err(message) => return usage_error(&message),
+    };
+    let locale = invocation.locale;
+    let ctx = Context {
+        locale,
+        ..Context::default()
+    };
+    // Documents (manifests, configs, SVG) are printed as-is by default
+    let format = invocation.format.unwrap_or(match tool.media_type() {
+        Some(_) => Format::Raw,
+        None => Format::Text,
+    });
+    let rendered = tool
+        .call(invocation.input, &ctx)
+        .and_then(|value| output::render(&value, format, tool.media_type()));
+    match rendered {
+        Ok(rendered) => {
+            print(rendered.body);
+            0
+        }
+        Err(e) => {
+            eprintln!("error: {}", e.message(locale));
+            1
+        }
+    }
+}
+
+fn parse(tool: &dyn DynTool, args: &[String]) -> Result<Invocation, String> {
+    let mut gen = openapi::generator();
+    let request = tool.request_schema(&mut gen);
+    let fields = openapi::query_parameters(&gen, &request);
+
+    let mut pairs: Vec<(String, String)> = Vec::new();
+    let mut json = None;
+    let mut format = None;
+    let mut locale = None;
+    let mut positional = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let (flag, inline) = match arg.strip_prefix("--") {
+            Some(flag) => match flag.split_once('=') {
+                Some((flag, value)) => (flag, Some(value.to_string())),
+                None => (flag, None),
+            },
+            None if arg == "-o" => ("output", None),
+            None => {
+                if positional.replace(arg.clone()).is_some() {
+                    return Err(format!("unexpected argument '{}'", arg));
+                }
+                continue;
+            }
+        };
+        let value = |args: &mut std::slice::Iter<String>, switch: bool| match &inline {
+            Some(value) => Ok(value.clone()),
+            // Booleans may be given as bare switches
+            None if switch
+                && !matches!(args.as_slice().first(), Some(a) if !a.starts_with("--")) =>
+            {
+                Ok("true".to_string())
+            }
+            None => args
+                .next()
+                .cloned()
+                .ok_or_else(|| format!("--{} needs a value", flag)),
+        };
+        match flag {
+            "output" => {
+                let name = value(&mut args, false)?;
+                format = Some(Format::from_name(&name).ok_or_else(|| {
+                    format!(
+                        "unknown output format '{}', expected one of {}",
+                        name,
+                        Format::NAMES.join(", ")
+                    )
+                })?);
+            }
+            "lang" => {
+                let tag = value(&mut args, false)?;
+                locale = Some(
+                    Locale::from_tag(&tag)
+                        .ok_or_else(|| format!("unsupported language '{}'", tag))?,
+                );
+            }
+            "json" => json = Some(value(&mut args, false)?),
+            _ => {
+                let field = fields
+                    .iter()
+                    .find(|f| normalize(f["name"].as_str().unwrap_or("")) == normalize(flag))
+                    .ok_or_else(|| {
+                        format!(
+                            "'{}' has no option --{}; see `devtools help {}`",
+                            tool.name(),
+                            flag,
+                            tool.name()
+                        )
+                    })?;
+                let name = field["name"].as_str().unwrap_or(flag).to_string();
+                pairs.push((
+                    name,
+                    value(&mut args, has_type(&field["schema"], "boolean"))?,
+                ));
+            }
+        }
+    }
+
+    if let Some(value) = positional {
+        let field = tool
+            .pipe_input()
+            .ok_or_else(|| format!("'{}' takes no VALUE, only options", tool.name()))?;
+        let value = if value == "-" {
+            read_stdin()?.trim_end_matches(['\r', '\n']).to_string()
+        } else {
+            value
+        };
+        pairs.insert(0, (field.to_string(), value));
+    }
+
+    let input = match json {
+        Some(_) if !pairs.is_empty() => {
+            return Err("--json cannot be combined with VALUE or field options".to_string())
+        }
+        Some(body) if body == "-" => Input::Body(read_stdin()?),
+        Some(body) => Input::Body(body),
+        None => Input::Query(serde_urlencoded::to_string(&pairs).map_err(|e| e.to_string())?),
+    };
+    Ok(Invocation {
+        input,
+        format,
+        locale: locale.unwrap_or_else(env_locale),
+    })
+}
+
+fn tool_help(tool: &dyn DynTool) -> String {
+    let mut gen = openapi::generator();
+    let request = tool.request_schema(&mut gen);
+    let mut help = format!(
+        "{} - {}\n\nUsage: devtools {}",
+        tool.name(),
+        tool.description(env_locale()),
+        tool.name()
+    );
+    if let Some(field) = tool.pipe_input() {
+        help.push_str(&format!(" [{}]", field.to_uppercase()));
+    }
+    help.push_str(" [OPTIONS]\n\nOptions:\n");
+    for field in openapi::query_parameters(&gen, &request) {
+        let name = flag_name(field["name"].as_str().unwrap_or(""));
+        let schema = &field["schema"];
+        let value = match schema.get("enum").and_then(Value::as_array) {
+            Some(values) => values
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+                .join("|"),
+            None => type_name(schema).to_string(),
+        };
+        let required = if field["required"] == true {
+            " (required)"
+        } else {
+            ""
+        };
+        help.push_str(&format!("  --{} <{}>{}\n", name, value, required));
+    }
+    help.push_str(
+        "  --json <request>    the whole request as JSON, for list and object fields\n  \
+         -o, --output <format>\n  \
+         --lang <en|zh-CN>",
+    );
+    help
+}
+
+// `targetPort` and `image_pull` are shown as `--target-port` and `--image-pull`.
+fn flag_name(field: &str) -> String {
+    let mut name = String::with_capacity(field.len() + 2);
+    for c in field.chars() {
+        if c.is_ascii_uppercase() {
+            name.push('-');
+            name.push(c.to_ascii_lowercase());
+        } else {
+            name.push(if c == '_' { '-' } else { c });
+        }
+    }
+    name
+}
+
+// `image_pull_policy`, `image-pull-policy` and `imagePullPolicy` all name the same field.
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| *c != '-' && *c != '_')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn has_type(schema: &Value, name: &str) -> bool {
+    match schema.get("type") {
+        Some(Value::String(t)) => t == name,
+        Some(Value::Array(types)) => types.iter().any(|t| t == name),
+        _ => false,
+    }
+}
+
+fn type_name(schema: &Value) -> &str {
+    match schema.get("type") {
+        Some(Value::String(t)) => t,
+        Some(Value::Array(types)) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|t| *t != "null")
+            .unwrap_or("value"),
+        _ => "value",
+    }
+}
+
+// LC_ALL, LC_MESSAGES and LANG in POSIX precedence, e.g. `zh_CN.UTF-8`.
+fn env_locale() -> Locale {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|value| Locale::from_tag(&value))
+        .unwrap_or_default()
+}
+
+fn read_stdin() -> Result<String, String> {
+    let mut input = String::new();
+    io::stdin()
+        .read_to_string(&mut input)
+        .map_err(|e| format!("cannot read stdin: {}", e))?;
+    Ok(input)
+}
+
+fn print(mut text: String) {
+    if !text.ends_with('\n') {
+        text.push('\n');
+    }
+    // A closed pipe (`devtools list | head`) is not worth reporting
+    let _ = io::stdout().write_all(text.as_bytes());
+}
+
+fn usage_error(message: &str) -> i32 {
+    eprintln!("error: {}\n\n{}", message, USAGE);
+    2
+}
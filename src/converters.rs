@@ -1,9 +1,13 @@
+#[cfg(any(feature = "text", feature = "formats"))]
+use crate::error::ToolError;
+#[cfg(feature = "formats")]
 use crate::models::*;
 
-pub fn convert_case(text: &str, mode: &str) -> String {
+#[cfg(feature = "text")]
+pub fn convert_case(text: &str, mode: &str) -> Result<String, ToolError> {
     let text = text.trim();
     if text.is_empty() {
-        return String::new();
+        return Ok(String::new());
     }
 
     let mut words = Vec::new();
@@ -34,7 +38,7 @@ pub fn convert_case(text: &str, mode: &str) -> String {
 
     let words: Vec<String> = words.into_iter().map(|w| w.to_lowercase()).collect();
 
-    match mode {
+    Ok(match mode {
         "camel" => {
             let mut res = String::new();
             for (i, w) in words.iter().enumerate() {
@@ -52,10 +56,11 @@ pub fn convert_case(text: &str, mode: &str) -> String {
         "constant" => words.join("_").to_uppercase(),
         "upper" => text.to_uppercase(),
         "lower" => text.to_lowercase(),
-        _ => text.to_string(),
-    }
+        _ => return Err(ToolError::invalid("mode", "case.unknown_mode", &[&mode])),
+    })
 }
 
+#[cfg(feature = "text")]
 fn capitalize(s: &str) -> String {
     let mut c = s.chars();
     match c.next() {
@@ -64,62 +69,50 @@ fn capitalize(s: &str) -> String {
     }
 }
 
-pub fn convert_unit(value: f64, type_: &str, from: &str, to: &str) -> UnitResponse {
-    let mut result = value;
-
-    if type_ == "storage" {
-        let to_bytes = |v: f64, u: &str| match u {
-            "B" => v,
-            "KB" => v * 1024.0,
-            "MB" => v * 1024.0 * 1024.0,
-            "GB" => v * 1024.0 * 1024.0 * 1024.0,
-            "TB" => v * 1024.0 * 1024.0 * 1024.0 * 1024.0,
-            "PB" => v * 1024.0 * 1024.0 * 1024.0 * 1024.0 * 1024.0,
-            _ => v,
-        };
-        let from_bytes = |v: f64, u: &str| match u {
-            "B" => v,
-            "KB" => v / 1024.0,
-            "MB" => v / (1024.0 * 1024.0),
-            "GB" => v / (1024.0 * 1024.0 * 1024.0),
-            "TB" => v / (1024.0 * 1024.0 * 1024.0 * 1024.0),
-            "PB" => v / (1024.0 * 1024.0 * 1024.0 * 1024.0 * 1024.0),
-            _ => v,
-        };
-
-        let bytes = to_bytes(value, from);
-        result = from_bytes(bytes, to);
-    } else if type_ == "time" {
-        let to_sec = |v: f64, u: &str| match u {
-            "ms" => v / 1000.0,
-            "s" => v,
-            "m" => v * 60.0,
-            "h" => v * 3600.0,
-            "d" => v * 86400.0,
-            _ => v,
-        };
-        let from_sec = |v: f64, u: &str| match u {
-            "ms" => v * 1000.0,
-            "s" => v,
-            "m" => v / 60.0,
-            "h" => v / 3600.0,
-            "d" => v / 86400.0,
-            _ => v,
-        };
+#[cfg(feature = "formats")]
+pub fn convert_unit(
+    value: f64,
+    type_: &str,
+    from: &str,
+    to: &str,
+) -> Result<UnitResponse, ToolError> {
+    // Size of one unit in the base unit (bytes / milliseconds), kept integral to avoid float drift
+    let factor: fn(&str) -> Option<f64> = match type_ {
+        "storage" => |u| match u {
+            "B" => Some(1.0),
+            "KB" => Some(1024.0),
+            "MB" => Some(1024.0 * 1024.0),
+            "GB" => Some(1024.0 * 1024.0 * 1024.0),
+            "TB" => Some(1024.0 * 1024.0 * 1024.0 * 1024.0),
+            "PB" => Some(1024.0 * 1024.0 * 1024.0 * 1024.0 * 1024.0),
+            _ => None,
+        },
+        "time" => |u| match u {
+            "ms" => Some(1.0),
+            "s" => Some(1000.0),
+            "m" => Some(60_000.0),
+            "h" => Some(3_600_000.0),
+            "d" => Some(86_400_000.0),
+            _ => None,
+        },
+        _ => return Err(ToolError::invalid("type", "unit.unknown_type", &[&type_])),
+    };
+    let from_factor = factor(from)
+        .ok_or_else(|| ToolError::invalid("from", "unit.unknown_unit", &[&type_, &from]))?;
+    let to_factor =
+        factor(to).ok_or_else(|| ToolError::invalid("to", "unit.unknown_unit", &[&type_, &to]))?;
+    let result = value * from_factor / to_factor;
 
-        let secs = to_sec(value, from);
-        result = from_sec(secs, to);
-    }
-
-    UnitResponse {
+    Ok(UnitResponse {
         result,
         value,
         from: from.to_string(),
         to: to.to_string(),
         type_: type_.to_string(),
-    }
+    })
 }
 
+#[cfg(feature = "crypto")]
 pub fn obfuscate_js(js: &str) -> String {
     let mut res = String::from("eval(\"");
     for b in js.bytes() {
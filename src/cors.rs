@@ -0,0 +1,164 @@
+// CORS for `/api/*`. Configured from wrangler vars; with no CORS_ALLOWED_ORIGINS set no
+// headers are added and browsers keep the same-origin behaviour.
+
+const DEFAULT_METHODS: &str = "GET, POST, OPTIONS";
+const DEFAULT_HEADERS: &str =
+    "Content-Type, Accept, Authorization, X-API-Key, MCP-Protocol-Version, If-None-Match";
+const DEFAULT_MAX_AGE: u32 = 86400;
+// Lets browser clients read the download filename, their rate limit state, request ID, ETag
+// and the seed of a streamed generator
+const EXPOSE_HEADERS: &str =
+    "Content-Disposition, Retry-After, X-RateLimit-Limit, X-RateLimit-Remaining, X-RateLimit-Reset, X-Request-Id, ETag, X-Seed";
+
+pub struct Cors {
+    // `*`, exact origins (`https://app.example.com`) or subdomain wildcards
+    // (`https://*.example.com`)
+    origins: Vec<String>,
+    methods: String,
+    headers: String,
+    max_age: u32,
+}
+
+impl Cors {
+    // Arguments are the raw var values: comma-separated origins, methods and headers, and
+    // max-age in seconds. Missing or empty values fall back to the defaults above.
+    pub fn new(
+        origins: Option<String>,
+        methods: Option<String>,
+        headers: Option<String>,
+        max_age: Option<String>,
+    ) -> Self {
+        let non_empty = |v: Option<String>| v.filter(|v| !v.trim().is_empty());
+        Cors {
+            origins: origins
+                .unwrap_or_default()
+                .split(',')
+                .map(|o| o.trim().trim_end_matches('/').to_string())
+                .filter(|o| !o.is_empty())
+                .collect(),
+            methods: non_empty(methods).unwrap_or_else(|| DEFAULT_METHODS.to_string()),
+            headers: non_empty(headers).unwrap_or_else(|| DEFAULT_HEADERS.to_string()),
+            max_age: max_age
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or(DEFAULT_MAX_AGE),
+        }
+    }
+
+    pub fn applies(path: &str) -> bool {
+        path.starts_with("/api/")
+    }
+
+    // Headers for a response to `origin`; empty when the origin is not allowed. Preflight
+    // responses also carry the allowed methods, headers and max-age.
+    pub fn headers(&self, origin: Option<&str>, preflight: bool) -> Vec<(&'static str, String)> {
+        let origin = match origin {
+            Some(origin) => origin,
+            None => return Vec::new(),
+        };
+        let mut out = Vec::new();
+        if self.origins.iter().any(|o| o == "*") {
+            out.push(("Access-Control-Allow-Origin", "*".to_string()));
+        } else if self.origins.iter().any(|o| matches(o, origin)) {
+            out.push(("Access-Control-Allow-Origin", origin.to_string()));
+            out.push(("Vary", "Origin".to_string()));
+        } else {
+            return out;
+        }
+        if preflight {
+            out.push(("Access-Control-Allow-Methods", self.methods.clone()));
+            out.push(("Access-Control-Allow-Headers", self.headers.clone()));
+            out.push(("Access-Control-Max-Age", self.max_age.to_string()));
+        } else {
+            out.push(("Access-Control-Expose-Headers", EXPOSE_HEADERS.to_string()));
+        }
+        out
+    }
+}
+
+fn matches(pattern: &str, origin: &str) -> bool {
+    match pattern.find("://*.") {
+        Some(i) => {
+            let (scheme, suffix) = (&pattern[..i + 3], &pattern[i + 4..]);
+            origin.starts_with(scheme)
+                && origin.ends_with(suffix)
+                && origin.len() > scheme.len() + suffix.len()
+        }
+        None => pattern.eq_ignore_ascii_case(origin),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cors(origins: &str) -> Cors {
+        Cors::new(
+            Some(origins.to_string()),
+            None,
+            None,
+            Some("600".to_string()),
+        )
+    }
+
+    fn header<'a>(headers: &'a [(&str, String)], name: &str) -> Option<&'a str> {
+        headers
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    #[test]
+    fn allowed_origins_are_echoed() {
+        let listed = cors("https://app.example.com/, https://*.example.org");
+        for origin in ["https://app.example.com", "https://a.b.example.org"] {
+            let headers = listed.headers(Some(origin), false);
+            assert_eq!(
+                header(&headers, "Access-Control-Allow-Origin"),
+                Some(origin)
+            );
+            assert_eq!(header(&headers, "Vary"), Some("Origin"));
+            assert!(header(&headers, "Access-Control-Expose-Headers")
+                .unwrap()
+                .contains("X-Seed"));
+            assert_eq!(header(&headers, "Access-Control-Allow-Methods"), None);
+        }
+
+        let headers = cors("*").headers(Some("https://any.test"), false);
+        assert_eq!(header(&headers, "Access-Control-Allow-Origin"), Some("*"));
+        assert_eq!(header(&headers, "Vary"), None);
+    }
+
+    #[test]
+    fn other_origins_get_no_headers() {
+        let cors = cors("https://app.example.com, https://*.example.org");
+        for origin in [
+            "https://evil.test",
+            "http://app.example.com",
+            "https://example.org",
+            "https://evilexample.org",
+        ] {
+            assert!(cors.headers(Some(origin), false).is_empty(), "{}", origin);
+            assert!(cors.headers(Some(origin), true).is_empty(), "{}", origin);
+        }
+        assert!(cors.headers(None, false).is_empty());
+        assert!(Cors::new(None, None, None, None)
+            .headers(Some("https://app.example.com"), true)
+            .is_empty());
+    }
+
+    #[test]
+    fn preflight_lists_methods_headers_and_max_age() {
+        let headers =
+            cors("https://app.example.com").headers(Some("https://app.example.com"), true);
+        assert_eq!(
+            header(&headers, "Access-Control-Allow-Methods"),
+            Some(DEFAULT_METHODS)
+        );
+        assert_eq!(
+            header(&headers, "Access-Control-Allow-Headers"),
+            Some(DEFAULT_HEADERS)
+        );
+        assert_eq!(header(&headers, "Access-Control-Max-Age"), Some("600"));
+        assert_eq!(header(&headers, "Access-Control-Expose-Headers"), None);
+    }
+}
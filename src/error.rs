@@ -0,0 +1,141 @@
+use crate::i18n::{Locale, Message};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::fmt;
+
+// Every failure of an /api route is reported as one of these, serialized as
+// `{"error": {"code": "...", "message": "...", "field": "..."}}` with the message in the
+// caller's locale.
+#[derive(Debug)]
+pub enum ToolError {
+    // The body (or query string) does not parse, or does not match the tool's request shape.
+    MalformedBody(String),
+    // The body is well-formed but a field's value cannot be processed.
+    InvalidInput {
+        field: &'static str,
+        message: Message,
+    },
+    NotFound(Message),
+    // A request exceeds one of the server-side limits, e.g. too many batch items.
+    LimitExceeded {
+        limit: &'static str,
+        max: usize,
+        actual: usize,
+    },
+    // No API key, or one that is not configured.
+    Unauthorized(Message),
+    // A valid API key without the scope for this tool.
+    Forbidden(Message),
+    // The route exists, but not for this method.
+    MethodNotAllowed(Message),
+    // The route only speaks WebSocket.
+    UpgradeRequired(Message),
+    // The client used up its token bucket for a route.
+    RateLimited {
+        route: String,
+        retry_after: u64,
+    },
+    Internal(String),
+}
+
+impl ToolError {
+    pub fn invalid(field: &'static str, key: &'static str, args: &[&dyn fmt::Display]) -> Self {
+        ToolError::InvalidInput {
+            field,
+            message: Message::new(key, args),
+        }
+    }
+
+    pub fn unknown_tool(name: &str) -> Self {
+        ToolError::NotFound(Message::new("error.unknown_tool", &[&name]))
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            ToolError::MalformedBody(_) => "malformed_body",
+            ToolError::InvalidInput { .. } => "invalid_input",
+            ToolError::NotFound(_) => "not_found",
+            ToolError::LimitExceeded { .. } => "limit_exceeded",
+            ToolError::Unauthorized(_) => "unauthorized",
+            ToolError::Forbidden(_) => "forbidden",
+            ToolError::MethodNotAllowed(_) => "method_not_allowed",
+            ToolError::UpgradeRequired(_) => "upgrade_required",
+            ToolError::RateLimited { .. } => "rate_limited",
+            ToolError::Internal(_) => "internal_error",
+        }
+    }
+
+    pub fn status(&self) -> u16 {
+        match self {
+            ToolError::MalformedBody(_) => 400,
+            ToolError::InvalidInput { .. } => 422,
+            ToolError::NotFound(_) => 404,
+            ToolError::LimitExceeded { .. } => 413,
+            ToolError::Unauthorized(_) => 401,
+            ToolError::Forbidden(_) => 403,
+            ToolError::MethodNotAllowed(_) => 405,
+            ToolError::UpgradeRequired(_) => 426,
+            ToolError::RateLimited { .. } => 429,
+            ToolError::Internal(_) => 500,
+        }
+    }
+
+    pub fn field(&self) -> Option<&'static str> {
+        match self {
+            ToolError::InvalidInput { field, .. } => Some(field),
+            _ => None,
+        }
+    }
+
+    // Details from parsers (serde, the worker runtime) are passed through untranslated.
+    pub fn message(&self, locale: Locale) -> String {
+        let message = match self {
+            ToolError::MalformedBody(detail) => Message::new("error.malformed", &[detail]),
+            ToolError::InvalidInput { message, .. }
+            | ToolError::NotFound(message)
+            | ToolError::Unauthorized(message)
+            | ToolError::Forbidden(message)
+            | ToolError::MethodNotAllowed(message)
+            | ToolError::UpgradeRequired(message) => return message.render(locale),
+            ToolError::LimitExceeded { limit, max, actual } => {
+                Message::new("error.limit_exceeded", &[limit, max, actual])
+            }
+            ToolError::RateLimited { route, retry_after } => {
+                Message::new("error.rate_limited", &[route, retry_after])
+            }
+            ToolError::Internal(detail) => Message::new("error.internal", &[detail]),
+        };
+        message.render(locale)
+    }
+
+    pub fn envelope(&self, locale: Locale) -> ErrorEnvelope {
+        ErrorEnvelope {
+            error: ErrorBody {
+                code: self.code(),
+                message: self.message(locale),
+                field: self.field(),
+            },
+        }
+    }
+}
+
+impl fmt::Display for ToolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message(Locale::En))
+    }
+}
+
+impl std::error::Error for ToolError {}
+
+#[derive(Serialize, JsonSchema)]
+pub struct ErrorEnvelope {
+    pub error: ErrorBody,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct ErrorBody {
+    pub code: &'static str,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<&'static str>,
+}
@@ -1,9 +1,27 @@
+#[cfg(any(feature = "generators", feature = "text"))]
 use crate::models::*;
+#[cfg(feature = "generators")]
 use rand::seq::SliceRandom;
+#[cfg(feature = "generators")]
 use rand::Rng;
 
-pub fn generate_lorem(count: usize, mode: &str) -> String {
-    let words = [
+#[cfg(feature = "generators")]
+pub fn generate_lorem(count: usize, mode: &str, rng: impl Rng + 'static) -> String {
+    let separator = if mode == "words" || mode == "sentences" {
+        " "
+    } else {
+        "\n\n"
+    };
+    lorem(mode, rng)
+        .take(count)
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+// Endless words, sentences or paragraphs, one per item.
+#[cfg(feature = "generators")]
+pub fn lorem(mode: &str, mut rng: impl Rng + 'static) -> impl Iterator<Item = String> {
+    const WORDS: [&str; 19] = [
         "lorem",
         "ipsum",
         "dolor",
@@ -24,54 +42,45 @@ pub fn generate_lorem(count: usize, mode: &str) -> String {
         "magna",
         "aliqua",
     ];
-    let mut rng = rand::thread_rng();
-    let mut result = String::new();
-
-    if mode == "words" {
-        for i in 0..count {
-            if i > 0 {
-                result.push(' ');
-            }
-            result.push_str(words.choose(&mut rng).unwrap());
-        }
-    } else if mode == "sentences" {
-        for i in 0..count {
-            if i > 0 {
-                result.push(' ');
-            }
+    let mode = mode.to_string();
+    std::iter::repeat_with(move || {
+        if mode == "words" {
+            WORDS.choose(&mut rng).unwrap().to_string()
+        } else if mode == "sentences" {
             let len = rng.gen_range(5..15);
             let mut sentence = String::new();
             for j in 0..len {
                 if j > 0 {
                     sentence.push(' ');
                 }
-                sentence.push_str(words.choose(&mut rng).unwrap());
+                sentence.push_str(WORDS.choose(&mut rng).unwrap());
             }
             // Capitalize first letter and add period
             let mut chars = sentence.chars();
+            let mut result = String::with_capacity(sentence.len() + 1);
             if let Some(first) = chars.next() {
                 result.push(first.to_ascii_uppercase());
                 result.push_str(chars.as_str());
             }
             result.push('.');
+            result
+        } else {
+            // paragraphs
+            "Lorem ipsum dolor sit amet, consectetur adipiscing elit. Sed do eiusmod tempor incididunt ut labore et dolore magna aliqua.".to_string()
         }
-    } else {
-        // paragraphs
-        for i in 0..count {
-            if i > 0 {
-                result.push_str("\n\n");
-            }
-            result.push_str("Lorem ipsum dolor sit amet, consectetur adipiscing elit. Sed do eiusmod tempor incididunt ut labore et dolore magna aliqua.");
-        }
-    }
-    result
+    })
+}
+
+#[cfg(feature = "generators")]
+pub fn generate_fake_users(count: usize, locale: &str, rng: impl Rng + 'static) -> Vec<FakeUser> {
+    fake_users(locale, rng).take(count).collect()
 }
 
-pub fn generate_fake_users(count: usize, locale: &str) -> Vec<FakeUser> {
-    let mut users = Vec::new();
-    let mut rng = rand::thread_rng();
+#[cfg(feature = "generators")]
+pub fn fake_users(locale: &str, mut rng: impl Rng + 'static) -> impl Iterator<Item = FakeUser> {
+    let cn = locale == "cn";
 
-    let (first_names, last_names, cities) = if locale == "cn" {
+    let (first_names, last_names, cities) = if cn {
         (
             vec!["伟", "芳", "娜", "敏", "静", "秀英", "丽", "强", "磊", "军"],
             vec!["王", "李", "张", "刘", "陈", "杨", "黄", "赵", "吴", "周"],
@@ -96,13 +105,13 @@ pub fn generate_fake_users(count: usize, locale: &str) -> Vec<FakeUser> {
         "example.com",
     ];
 
-    for _ in 0..count {
+    std::iter::repeat_with(move || {
         let first = first_names.choose(&mut rng).unwrap();
         let last = last_names.choose(&mut rng).unwrap();
         let domain = domains.choose(&mut rng).unwrap();
         let city = cities.choose(&mut rng).unwrap();
 
-        let (name, email, address, phone) = if locale == "cn" {
+        let (name, email, address, phone) = if cn {
             (
                 format!("{}{}", last, first),
                 format!("user{}@{}", rng.gen_range(1000..9999), domain),
@@ -131,23 +140,31 @@ pub fn generate_fake_users(count: usize, locale: &str) -> Vec<FakeUser> {
             )
         };
 
-        users.push(FakeUser {
+        FakeUser {
             name,
             email,
             address,
             phone,
-        });
-    }
-    users
+        }
+    })
 }
 
-pub fn generate_credit_cards(count: usize, issuer: &str) -> Vec<CreditCard> {
-    let mut cards = Vec::new();
-    let mut rng = rand::thread_rng();
+#[cfg(feature = "generators")]
+pub fn generate_credit_cards(
+    count: usize,
+    issuer: &str,
+    rng: impl Rng + 'static,
+) -> Vec<CreditCard> {
+    credit_cards(issuer, rng).take(count).collect()
+}
+
+#[cfg(feature = "generators")]
+pub fn credit_cards(issuer: &str, mut rng: impl Rng + 'static) -> impl Iterator<Item = CreditCard> {
+    let issuer = issuer.to_string();
 
-    for _ in 0..count {
+    std::iter::repeat_with(move || {
         // 1. 确定前缀和长度
-        let (len, mut digits) = match issuer {
+        let (len, mut digits) = match issuer.as_str() {
             "mastercard" => (16, vec![5, rng.gen_range(1..=5)]),
             "amex" => (15, vec![3, if rng.gen_bool(0.5) { 4 } else { 7 }]),
             "discover" => (16, vec![6, 0, 1, 1]),
@@ -174,16 +191,16 @@ pub fn generate_credit_cards(count: usize, issuer: &str) -> Vec<CreditCard> {
         let check_digit = (10 - (sum % 10)) % 10;
         digits.push(check_digit);
 
-        cards.push(CreditCard {
+        CreditCard {
             number: digits.iter().map(|d| d.to_string()).collect(),
-            issuer: issuer.to_string(),
+            issuer: issuer.clone(),
             expiry: format!("{:02}/{:02}", rng.gen_range(1..=12), rng.gen_range(25..30)),
             cvv: format!("{:03}", rng.gen_range(100..999)),
-        });
-    }
-    cards
+        }
+    })
 }
 
+#[cfg(feature = "text")]
 pub fn generate_custom_regex(
     starts: &str,
     not_starts: &str,
@@ -217,8 +234,5 @@ pub fn generate_custom_regex(
     }
     p.push('$');
 
-    RegexBuildResponse {
-        pattern: p,
-        error: None,
-    }
+    RegexBuildResponse { pattern: p }
 }
@@ -1,4 +1,59 @@
-pub fn get_homepage() -> &'static str {
+use crate::toolset::ToolSet;
+use std::borrow::Cow;
+
+// Menu entries that call no tool and always stay.
+const STATIC_ENTRIES: &[&str] = &["whoami", "ssh-key", "disclaimer"];
+
+// Menu entries whose panel calls tools under other names; the rest call the tool they are
+// named after.
+const MENU_TOOLS: &[(&str, &[&str])] = &[
+    ("regex", &["regex-gen", "regex-build"]),
+    ("k8s", &["k8s-yaml"]),
+    ("git", &["git", "git-cmd"]),
+    ("cc", &["credit-card"]),
+    ("url-parser", &["url"]),
+    ("yaml", &["yaml-to-toml"]),
+    ("toml2yaml", &["toml-to-yaml"]),
+    ("hash", &["md5"]),
+    ("jsenc", &["js-enc"]),
+    ("qr", &["qrcode"]),
+    ("unit", &["unit-convert"]),
+];
+
+// The homepage without the menu entries of disabled tools. An entry goes once every tool
+// its panel calls is disabled or not compiled in.
+pub fn get_homepage(tools: &ToolSet) -> Cow<'static, str> {
+    let page = homepage();
+    let hidden = |line: &str| {
+        let id = match line
+            .split("onclick=\"nav('")
+            .nth(1)
+            .and_then(|rest| rest.split('\'').next())
+        {
+            Some(id) => id,
+            None => return false,
+        };
+        if STATIC_ENTRIES.contains(&id) {
+            return false;
+        }
+        let called: &[&str] = match MENU_TOOLS.iter().find(|(entry, _)| *entry == id) {
+            Some((_, names)) => names,
+            None => std::slice::from_ref(&id),
+        };
+        !called.iter().any(|name| tools.find(name).is_some())
+    };
+    if !page.lines().any(hidden) {
+        return Cow::Borrowed(page);
+    }
+    Cow::Owned(
+        page.lines()
+            .filter(|line| !hidden(line))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
+}
+
+fn homepage() -> &'static str {
     r####"
 <!DOCTYPE html>
 <html lang="zh-CN">
@@ -304,6 +359,9 @@ pub fn get_homepage() -> &'static str {
             border: none; 
         }
 
+        /* 分享按钮 */
+        #share-btn { position: fixed; right: 30px; bottom: 30px; z-index: 100; }
+
         /* 提示框样式 */
         #toast { 
             position: fixed; 
@@ -358,8 +416,9 @@ pub fn get_homepage() -> &'static str {
 </head>
 <body>
     <div id="toast">已复制</div>
+    <button id="share-btn" class="btn" onclick="shareTool()" title="生成可分享的链接">🔗 分享</button>
     <aside class="sidebar">
-        <div class="logo">🦀 Rust 工具箱</div>
+        <div class="logo">🦀 Rust 工具箱 <a href="/api/docs" style="float:right; font-size:12px; font-weight:normal; color:#94a3b8; text-decoration:none; line-height:24px;">API 文档</a></div>
         <div style="padding: 15px 10px 5px;">
             <input type="text" id="menu-search" placeholder="🔍 搜索工具..." oninput="filterMenu()" style="background:rgba(255,255,255,0.1); border:1px solid rgba(255,255,255,0.1); color:white; padding:10px 12px; font-size:13px; width:100%; box-shadow:none;">
         </div>
@@ -1922,6 +1981,7 @@ enabled = true"></textarea></div><div class="editor-box"><div class="editor-head
             document.getElementById(id).classList.add('active'); 
             document.querySelectorAll('.link').forEach(l=>l.classList.remove('active')); 
             el.classList.add('active'); 
+            document.getElementById('share-btn').style.display = Object.keys(SHARE_FILL).some(t => panelOf(t) === id) ? '' : 'none';
         }
         function toast(m, t='success') { 
             const el = document.getElementById('toast'); 
@@ -1932,14 +1992,26 @@ enabled = true"></textarea></div><div class="editor-box"><div class="editor-head
         }
         function copy(id) { const e=document.getElementById(id); const t=e.tagName==='TEXTAREA'||e.tagName==='INPUT'?e.value:e.innerText; if(!t)return toast('无内容', 'error'); navigator.clipboard.writeText(t).then(()=>toast('已复制')); }
         function setVal(id,v) { document.getElementById(id).value=v; }
+        // 部署启用了 API_KEYS 时，密钥保存在 localStorage 中随请求发送
+        function authHeaders() { const k = localStorage.getItem('apiKey'); return k ? {'X-API-Key': k} : {}; }
+        function askKey() { const k = prompt('此服务需要 API Key'); if (!k) return false; localStorage.setItem('apiKey', k.trim()); return true; }
         async function post(u,d) { 
+            if (u !== '/share') { lastCalls.delete(u.slice(1)); lastCalls.set(u.slice(1), d); }
             try {
+                // 页面是中文，命令说明和错误信息也要中文
                 const r = await fetch('/api'+u, {
                     method: 'POST',
-                    headers: {'Content-Type': 'application/json'},
+                    headers: {'Content-Type': 'application/json', 'Accept-Language': 'zh-CN', ...authHeaders()},
                     body: JSON.stringify(d)
                 });
-                if(!r.ok) throw await r.text();
+                if(r.status === 401 && askKey()) return post(u,d);
+                if(!r.ok) {
+                    // 后端统一返回 {"error": {"code", "message", "field"}}
+                    const t = await r.text();
+                    let m = t;
+                    try { m = JSON.parse(t).error.message || t; } catch(_) {}
+                    throw m;
+                }
                 return await r.json();
             } catch(e) {
                 let msg = e;
@@ -1952,6 +2024,75 @@ enabled = true"></textarea></div><div class="editor-box"><div class="editor-head
             } 
         }
 
+        // 分享链接：post() 按工具记录最近一次请求，分享当前面板最近调用的那个工具；
+        // 打开 /s/<id> 会跳转到 /#s=<id>，页面取回保存的输入、回填表单并重新执行
+        const lastCalls = new Map();
+        // 面板 id 与工具名不同的工具，与 html.rs 中的 MENU_TOOLS 一致
+        const TOOL_PANELS = {'regex-gen':'regex', 'regex-build':'regex', 'k8s-yaml':'k8s', 'git-cmd':'git', 'credit-card':'cc', 'yaml-to-toml':'yaml', 'toml-to-yaml':'toml2yaml', 'md5':'hash', 'js-enc':'jsenc', 'qrcode':'qr', 'unit-convert':'unit'};
+        function panelOf(tool) { return TOOL_PANELS[tool] || tool; }
+        const SHARE_FILL = {
+            cron: i => {
+                const f = (i.cron || '').trim().split(/\s+/);
+                ['c-m','c-h','c-d','c-mo','c-w'].forEach((k, n) => setVal(k, f[n] || '*'));
+                setVal('cron-res', i.cron || '');
+                doCron();
+            },
+            subnet: i => { setVal('sn-ip', i.ip || ''); setVal('sn-cidr', i.cidr ?? 24); doSubnet(); },
+            sql: i => { setVal('sql-in', i.sql || ''); doSql(); },
+            diff: i => { setVal('diff-a', i.old || ''); setVal('diff-b', i.new || ''); doDiff(); },
+            json: i => { setVal('json-in', i.input || ''); doJson('fmt'); },
+            jwt: i => { setVal('jwt-in', i.token || ''); doJwt(); },
+            'regex-gen': i => { setVal('reg-key', i.key || 'email'); doRegGen(); },
+            'regex-build': i => {
+                document.getElementById('reg-builder').style.display = 'block';
+                setVal('rb-start', i.starts_with || ''); setVal('rb-not-start', i.not_starts_with || '');
+                setVal('rb-end', i.ends_with || ''); setVal('rb-not-end', i.not_ends_with || '');
+                setVal('rb-has', i.contains || ''); setVal('rb-not-has', i.not_contains || '');
+                doRegBuild();
+            },
+            nginx: i => {
+                const ids = {domain:'ng-domain', port:'ng-port', root:'ng-root', upstream:'ng-upstream', ssl_cert:'ng-crt', ssl_key:'ng-key', client_max_body_size:'ng-size', keepalive_timeout:'ng-ka', proxy_connect_timeout:'ng-pct', proxy_read_timeout:'ng-prt', proxy_send_timeout:'ng-pst'};
+                Object.entries(ids).forEach(([k, id]) => setVal(id, i[k] ?? ''));
+                document.getElementById('ng-ssl').checked = !!i.https;
+                document.getElementById('ng-force').checked = !!i.force_https;
+                document.getElementById('ng-gzip').checked = !!i.gzip;
+                document.getElementById('ng-locs-container').innerHTML = '';
+                ngLocCount = 0;
+                (i.locations || []).forEach(l => {
+                    addNginxLocation();
+                    const div = document.getElementById(`ng-loc-${ngLocCount - 1}`);
+                    ['path', 'proxy', 'root'].forEach(k => div.querySelector(`[name="${k}"]`).value = l[k] || '');
+                    div.querySelector('[name="spa"]').checked = !!l.spa;
+                });
+                toggleSslInputs();
+                doNginx();
+            }
+        };
+        async function shareTool() {
+            const panel = document.querySelector('.panel.active').id;
+            const call = [...lastCalls].reverse().find(([t]) => panelOf(t) === panel && SHARE_FILL[t]);
+            if (!call) return toast('请先运行一次再分享', 'error');
+            try {
+                const d = await post('/share', {tool: call[0], input: call[1]});
+                await navigator.clipboard.writeText(location.origin + d.url);
+                toast('分享链接已复制，' + new Date(d.expires_at * 1000).toLocaleDateString() + ' 前有效');
+            } catch(e) {}
+        }
+        async function openShare() {
+            const m = location.hash.match(/^#s=(\w+)$/);
+            if (!m) return;
+            const r = await fetch('/api/share/' + m[1], {headers: {'Accept-Language': 'zh-CN', ...authHeaders()}});
+            if (r.status === 401 && askKey()) return openShare();
+            history.replaceState(null, '', '/');
+            if (!r.ok) return toast('分享链接不存在或已过期', 'error');
+            const s = await r.json();
+            const panel = panelOf(s.tool);
+            const link = document.querySelector(`.link[onclick^="nav('${panel}'"]`);
+            if (!link || !SHARE_FILL[s.tool]) return toast('此部署无法打开该分享', 'error');
+            nav(panel, link);
+            SHARE_FILL[s.tool](s.input);
+        }
+
         // Menu Filter
         function filterMenu() {
             const v = document.getElementById('menu-search').value.toLowerCase();
@@ -1989,9 +2130,6 @@ enabled = true"></textarea></div><div class="editor-box"><div class="editor-head
                 let text = document.getElementById('qr-text').value;
                 if(!text) return toast('请输入内容', 'error');
                 let d = await post('/qrcode', {text: text});
-                if (d.error) {
-                    return toast('生成失败: ' + d.error, 'error');
-                }
 
                 const img = new Image();
                 img.src = 'data:image/svg+xml;base64,' + btoa(unescape(encodeURIComponent(d.svg)));
@@ -2075,10 +2213,6 @@ enabled = true"></textarea></div><div class="editor-box"><div class="editor-head
             
             try {
                 let d = await post('/subnet', {ip: ip, cidr: cidrNum});
-                if (!d.valid) {
-                    return toast('计算失败: 无效的 IP 或 CIDR', 'error');
-                }
-
                 document.getElementById('sn-cidr-res').innerText = d.cidr;
                 document.getElementById('sn-mask').innerText = d.mask;
                 document.getElementById('sn-wild').innerText = d.wildcard;
@@ -2100,15 +2234,13 @@ enabled = true"></textarea></div><div class="editor-box"><div class="editor-head
         async function doCol() { 
             try{
                 let d=await post('/color',{input:document.getElementById('col-i').value}); 
-                if(d.valid){
-                    document.getElementById('c-hex').innerText=d.hex; 
-                    document.getElementById('c-rgb').innerText=d.rgb;
-                    document.getElementById('c-hsl').innerText=d.hsl||'-'; 
-                    document.getElementById('c-cmyk').innerText=d.cmyk||'-';
-                    document.getElementById('col-p').value=d.hex;
-                    let p=document.getElementById('color-preview'); p.style.backgroundColor=d.hex; p.style.color=d.hex>'#888888'?'#000':'#fff';
-                    p.innerText = d.hex;
-                }
+                document.getElementById('c-hex').innerText=d.hex; 
+                document.getElementById('c-rgb').innerText=d.rgb;
+                document.getElementById('c-hsl').innerText=d.hsl||'-'; 
+                document.getElementById('c-cmyk').innerText=d.cmyk||'-';
+                document.getElementById('col-p').value=d.hex;
+                let p=document.getElementById('color-preview'); p.style.backgroundColor=d.hex; p.style.color=d.hex>'#888888'?'#000':'#fff';
+                p.innerText = d.hex;
             }catch(e){} 
         }
 
@@ -2231,13 +2363,10 @@ enabled = true"></textarea></div><div class="editor-box"><div class="editor-head
           }
           try {
             let d=await post('/cron',{cron:v});
-            document.getElementById('cron-out').innerText=d.valid?d.next_runs.join('\n'):d.error;
-            if(!d.valid) {
-              toast('Cron 表达式格式错误', 'error');
-            }
+            document.getElementById('cron-out').innerText=d.next_runs.join('\n');
           } catch(e) {
-            toast('Cron 验证失败', 'error');
-            document.getElementById('cron-out').innerText='';
+            toast('Cron 表达式格式错误', 'error');
+            document.getElementById('cron-out').innerText=typeof e === 'string' ? e : '';
           }
         }
         async function doMd5() { 
@@ -2278,18 +2407,13 @@ enabled = true"></textarea></div><div class="editor-box"><div class="editor-head
           }
           try {
             let d = await post('/date', { input: v });
-            if (d.valid) {
-                document.getElementById('ts-s').innerText = d.unix_sec;
-                document.getElementById('ts-ms').innerText = d.unix_milli;
-                document.getElementById('ts-iso').innerText = d.iso_8601;
-                document.getElementById('ts-utc').innerText = d.human_utc;
-                document.getElementById('ts-loc').innerText = new Date(d.unix_milli).toLocaleString();
-            } else {
-                toast(d.error || '无效的日期', 'error');
-                ['ts-s', 'ts-ms', 'ts-iso', 'ts-utc', 'ts-loc'].forEach(id => document.getElementById(id).innerText = '');
-            }
+            document.getElementById('ts-s').innerText = d.unix_sec;
+            document.getElementById('ts-ms').innerText = d.unix_milli;
+            document.getElementById('ts-iso').innerText = d.iso_8601;
+            document.getElementById('ts-utc').innerText = d.human_utc;
+            document.getElementById('ts-loc').innerText = new Date(d.unix_milli).toLocaleString();
           } catch(e) {
-            toast('时间转换失败', 'error');
+            toast(typeof e === 'string' ? e : '时间转换失败', 'error');
             ['ts-s', 'ts-ms', 'ts-iso', 'ts-utc', 'ts-loc'].forEach(id => document.getElementById(id).innerText = '');
           }
         }
@@ -2497,16 +2621,13 @@ enabled = true"></textarea></div><div class="editor-box"><div class="editor-head
         async function doJwt() { 
             try {
                 let d = await post('/jwt', {token: document.getElementById('jwt-in').value});
-                if (d.error) {
-                    toast(d.error, 'error');
-                    document.getElementById('jwt-h').value = '解析失败: ' + d.error;
-                    document.getElementById('jwt-p').value = '';
-                } else {
-                    document.getElementById('jwt-h').value = d.header;
-                    document.getElementById('jwt-p').value = d.payload;
-                    toast('解析成功');
-                }
-            } catch(e) {} 
+                document.getElementById('jwt-h').value = d.header;
+                document.getElementById('jwt-p').value = d.payload;
+                toast('解析成功');
+            } catch(e) {
+                document.getElementById('jwt-h').value = '解析失败: ' + e;
+                document.getElementById('jwt-p').value = '';
+            } 
         }
         async function doB64(a) { 
           let v=document.getElementById('b64-in').value;
@@ -2550,10 +2671,7 @@ enabled = true"></textarea></div><div class="editor-box"><div class="editor-head
               return;
             }
             let d=await post('/yaml-to-toml',{yaml: yamlInput});
-            if (d.error) {
-              document.getElementById('toml-output').value='转换失败：' + d.error;
-              toast('转换失败', 'error');
-            } else if (d && d.result) {
+            if (d && d.result) {
               document.getElementById('toml-output').value=d.result;
               toast('转换成功', 'success');
             } else {
@@ -2574,10 +2692,7 @@ enabled = true"></textarea></div><div class="editor-box"><div class="editor-head
               return;
             }
             let d=await post('/toml-to-yaml',{toml: tomlInput});
-            if (d.error) {
-              document.getElementById('yaml-output').value='转换失败：' + d.error;
-              toast('转换失败', 'error');
-            } else if (d && d.result) {
+            if (d && d.result) {
               document.getElementById('yaml-output').value=d.result;
               toast('转换成功', 'success');
             } else {
@@ -2591,7 +2706,7 @@ enabled = true"></textarea></div><div class="editor-box"><div class="editor-head
           }
         }
         function upChmod(c){let u=(document.getElementById('c_ur').checked?4:0)+(document.getElementById('c_uw').checked?2:0)+(document.getElementById('c_ux').checked?1:0),g=(document.getElementById('c_gr').checked?4:0)+(document.getElementById('c_gw').checked?2:0)+(document.getElementById('c_gx').checked?1:0),o=(document.getElementById('c_or').checked?4:0)+(document.getElementById('c_ow').checked?2:0)+(document.getElementById('c_ox').checked?1:0);if(c)document.getElementById('chmod-octal').value=""+u+g+o;else{let v=document.getElementById('chmod-octal').value;if(v.length===3){let n=v.split('').map(Number);if(n.every(x=>x>=0&&x<=7)){u=n[0];g=n[1];o=n[2];document.getElementById('c_ur').checked=u&4;document.getElementById('c_uw').checked=u&2;document.getElementById('c_ux').checked=u&1;document.getElementById('c_gr').checked=g&4;document.getElementById('c_gw').checked=g&2;document.getElementById('c_gx').checked=g&1;document.getElementById('c_or').checked=o&4;document.getElementById('c_ow').checked=o&2;document.getElementById('c_ox').checked=o&1}}}fetchChmod(document.getElementById('chmod-octal').value)}
-        async function fetchChmod(o){try{let f=document.getElementById('chmod-file').value;let d=await post('/chmod',{octal:o,file:f});if(d.valid)document.getElementById('chmod-command').innerText=d.command;}catch(e){} }
+        async function fetchChmod(o){try{let f=document.getElementById('chmod-file').value;if(!/^[0-7]{3}$/.test(o))return;let d=await post('/chmod',{octal:o,file:f});document.getElementById('chmod-command').innerText=d.command;}catch(e){} }
         async function doTar() { try{let d=await post('/tar',{op:document.getElementById('tar-op').value,comp:document.getElementById('tar-comp').value,verbose:document.getElementById('tar-v').checked,archive:document.getElementById('tar-arch').value,files:document.getElementById('tar-files').value});document.getElementById('tar-cmd').innerText=d.command;}catch(e){} }
         async function doPs() { 
             try {
@@ -3408,10 +3523,10 @@ enabled = true"></textarea></div><div class="editor-box"><div class="editor-head
 
             try {
                 let d = await post('/ansible', {
-                    play_name: document.getElementById('ans-name').value,
+                    playName: document.getElementById('ans-name').value,
                     hosts: document.getElementById('ans-hosts').value,
                     become: document.getElementById('ans-become').checked,
-                    gather_facts: document.getElementById('ans-facts').checked,
+                    gatherFacts: document.getElementById('ans-facts').checked,
                     vars: document.getElementById('ans-vars').value,
                     tasks: document.getElementById('ans-tasks').value,
                     handlers: document.getElementById('ans-handlers').value
@@ -3420,7 +3535,192 @@ enabled = true"></textarea></div><div class="editor-box"><div class="editor-head
             } catch(e) {}
         }
 
-        window.onload = () => { fillTime(); upCron(); upChmod(true); doTar(); doPs(); doTcpdump(); updateGitUI(); doGit(); doStrace(); doIostat(); doNice(); doLs(); doFirewall(); updateSysUI(); doSystemctl(); updateFindUI(); doFind(); doWhoami(); doRsync(); addStage(); addNginxLocation(); updateUnitUI(); updateGcUI(); doGitCheat(); doAwk(); doSed(); updateK8sUI(); updateAnsModUI(); };
+        window.onload = () => { fillTime(); upCron(); upChmod(true); doTar(); doPs(); doTcpdump(); updateGitUI(); doGit(); doStrace(); doIostat(); doNice(); doLs(); doFirewall(); updateSysUI(); doSystemctl(); updateFindUI(); doFind(); doWhoami(); doRsync(); addStage(); addNginxLocation(); updateUnitUI(); updateGcUI(); doGitCheat(); doAwk(); doSed(); updateK8sUI(); updateAnsModUI(); openShare(); };
+    </script>
+</body>
+</html>
+    "####
+}
+
+pub fn get_api_docs() -> &'static str {
+    r####"
+<!DOCTYPE html>
+<html lang="zh-CN">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>API 文档 - Rust 工具箱</title>
+    <style>
+        :root {
+            --primary: #0ea5e9; --sidebar-bg: #1e293b; --bg: #f8fafc; --card-bg: #ffffff;
+            --text: #334155; --text-muted: #64748b; --border: #e2e8f0; --res-bg: #f1f5f9;
+            --post: #49cc90; --get: #61affe; --err: #ef4444;
+        }
+        * { box-sizing: border-box; margin: 0; padding: 0; }
+        body { font-family: -apple-system, system-ui, sans-serif; display: flex; height: 100vh; background: var(--bg); color: var(--text); }
+        .sidebar { width: 240px; background: var(--sidebar-bg); color: #e2e8f0; overflow-y: auto; flex-shrink: 0; }
+        .logo { padding: 20px; font-weight: bold; font-size: 18px; color: var(--primary); border-bottom: 1px solid rgba(255,255,255,0.1); }
+        .logo a { color: inherit; text-decoration: none; }
+        .tag { padding: 14px 20px 6px; font-size: 12px; text-transform: uppercase; color: #94a3b8; letter-spacing: .05em; }
+        .sidebar a.op { display: block; padding: 6px 20px; color: #cbd5e1; text-decoration: none; font-size: 13px; font-family: monospace; }
+        .sidebar a.op:hover { background: rgba(255,255,255,0.06); color: white; }
+        main { flex: 1; overflow-y: auto; padding: 30px 40px; }
+        h1 { font-size: 22px; margin-bottom: 6px; }
+        .muted { color: var(--text-muted); font-size: 13px; }
+        .muted a { color: var(--primary); }
+        .card { background: var(--card-bg); border: 1px solid var(--border); border-radius: 8px; margin: 16px 0; }
+        .card-head { display: flex; align-items: center; gap: 12px; padding: 12px 16px; cursor: pointer; }
+        .method { color: white; font-weight: bold; font-size: 12px; padding: 4px 10px; border-radius: 4px; min-width: 56px; text-align: center; }
+        .method.post { background: var(--post); } .method.get { background: var(--get); }
+        .path { font-family: monospace; font-weight: bold; }
+        .card-body { display: none; padding: 0 16px 16px; border-top: 1px solid var(--border); }
+        .card.open .card-body { display: block; }
+        table { width: 100%; border-collapse: collapse; margin: 12px 0; font-size: 13px; }
+        th, td { text-align: left; padding: 6px 8px; border-bottom: 1px solid var(--border); vertical-align: top; }
+        th { color: var(--text-muted); font-weight: 600; }
+        td code { background: var(--res-bg); padding: 1px 5px; border-radius: 3px; }
+        .req { color: var(--err); }
+        textarea { width: 100%; min-height: 140px; font-family: monospace; font-size: 13px; padding: 10px; border: 1px solid var(--border); border-radius: 6px; }
+        button { margin: 10px 0; background: var(--primary); color: white; border: none; padding: 8px 18px; border-radius: 6px; cursor: pointer; }
+        pre { background: var(--res-bg); padding: 12px; border-radius: 6px; font-size: 13px; overflow-x: auto; white-space: pre-wrap; word-break: break-all; }
+        .status { font-size: 13px; font-weight: bold; margin-bottom: 6px; }
+        .status.err { color: var(--err); }
+    </style>
+</head>
+<body>
+    <aside class="sidebar">
+        <div class="logo"><a href="/">🦀 Rust 工具箱</a></div>
+        <nav id="nav"></nav>
+    </aside>
+    <main>
+        <h1 id="title">API 文档</h1>
+        <p class="muted">由 <a href="/api/openapi.json">/api/openapi.json</a> 生成，可直接在页面中调用接口。</p>
+        <div id="ops"></div>
+    </main>
+    <script>
+        let spec = null;
+
+        function resolve(schema) {
+            while (schema && schema.$ref) schema = spec.components.schemas[schema.$ref.split('/').pop()];
+            return schema || {};
+        }
+
+        function typeOf(s) {
+            if (s.$ref) return s.$ref.split('/').pop();
+            if (s.type === 'array') return typeOf(s.items || {}) + '[]';
+            return [].concat(s.type || 'any').join(' | ');
+        }
+
+        function example(s) {
+            s = resolve(s);
+            if ('default' in s) return s.default;
+            if (s.enum) return s.enum[0];
+            const t = [].concat(s.type)[0];
+            if (t === 'array') return s.items ? [example(s.items)] : [];
+            if (t === 'object') {
+                const o = {};
+                for (const [k, v] of Object.entries(s.properties || {})) o[k] = example(v);
+                return o;
+            }
+            return { string: '', integer: 0, number: 0, boolean: false, array: [], null: null }[t] ?? null;
+        }
+
+        function fieldTable(schema) {
+            let s = resolve(schema);
+            if (s.type === 'array') s = resolve(s.items);
+            const required = new Set(s.required || []);
+            const rows = Object.entries(s.properties || {}).map(([k, v]) => {
+                const notes = [];
+                if ('default' in v) notes.push('默认 <code>' + esc(JSON.stringify(v.default)) + '</code>');
+                if (v.enum) notes.push('可选 ' + v.enum.map(e => '<code>' + esc(e) + '</code>').join(' '));
+                return `<tr><td><code>${esc(k)}</code>${required.has(k) ? ' <span class="req">*</span>' : ''}</td><td>${esc(typeOf(v))}</td><td>${notes.join('<br>')}</td></tr>`;
+            });
+            return rows.length ? `<table><tr><th>字段</th><th>类型</th><th>说明</th></tr>${rows.join('')}</table>` : '';
+        }
+
+        function esc(s) {
+            return String(s).replace(/[&<>"]/g, c => ({ '&': '&amp;', '<': '&lt;', '>': '&gt;', '"': '&quot;' })[c]);
+        }
+
+        function render() {
+            document.getElementById('title').textContent = `${spec.info.title} ${spec.info.version}`;
+            const groups = {};
+            let i = 0;
+            for (const [path, item] of Object.entries(spec.paths)) {
+                for (const [method, op] of Object.entries(item)) {
+                    const tag = (op.tags || ['misc'])[0];
+                    (groups[tag] = groups[tag] || []).push({ id: 'op' + i++, path, method, op });
+                }
+            }
+            let nav = '', html = '';
+            for (const [tag, ops] of Object.entries(groups)) {
+                nav += `<div class="tag">${esc(tag)}</div>`;
+                for (const { id, path, method, op } of ops) {
+                    nav += `<a class="op" href="#${id}" onclick="openOp('${id}')">${esc(path.replace('/api/', ''))}</a>`;
+                    const body = op.requestBody ? op.requestBody.content['application/json'].schema : null;
+                    html += `<div class="card" id="${id}">
+                        <div class="card-head" onclick="this.parentNode.classList.toggle('open')">
+                            <span class="method ${method}">${method.toUpperCase()}</span>
+                            <span class="path">${esc(path)}</span>
+                            <span class="muted">${esc(op.summary || '')}</span>
+                        </div>
+                        <div class="card-body">
+                            ${body ? fieldTable(body) + `<textarea>${esc(JSON.stringify(example(body), null, 2))}</textarea>` : '<p class="muted" style="margin-top:12px">无请求体</p>'}
+                            <button onclick="tryOp('${id}', '${method}', '${path}')">发送请求</button>
+                            <div class="result"></div>
+                        </div>
+                    </div>`;
+                }
+            }
+            document.getElementById('nav').innerHTML = nav;
+            document.getElementById('ops').innerHTML = html;
+        }
+
+        function openOp(id) {
+            document.getElementById(id).classList.add('open');
+        }
+
+        async function tryOp(id, method, path) {
+            const card = document.getElementById(id);
+            const input = card.querySelector('textarea');
+            const out = card.querySelector('.result');
+            const init = { method: method.toUpperCase(), headers: authHeaders() };
+            if (input) {
+                init.headers['Content-Type'] = 'application/json';
+                init.body = input.value;
+            }
+            try {
+                const r = await fetch(path, init);
+                if (r.status === 401 && askKey()) return tryOp(id, method, path);
+                let text = await r.text();
+                try { text = JSON.stringify(JSON.parse(text), null, 2); } catch (e) {}
+                out.innerHTML = `<div class="status ${r.ok ? '' : 'err'}">HTTP ${r.status}</div><pre>${esc(text)}</pre>`;
+            } catch (e) {
+                out.innerHTML = `<div class="status err">${esc(e)}</div>`;
+            }
+        }
+
+        // 部署启用了 API_KEYS 时，密钥保存在 localStorage 中随请求发送
+        function authHeaders() {
+            const key = localStorage.getItem('apiKey');
+            return key ? { 'X-API-Key': key } : {};
+        }
+
+        function askKey() {
+            const key = prompt('此服务需要 API Key');
+            if (!key) return false;
+            localStorage.setItem('apiKey', key.trim());
+            return true;
+        }
+
+        async function load() {
+            const r = await fetch('/api/openapi.json', { headers: authHeaders() });
+            if (r.status === 401 && askKey()) return load();
+            spec = await r.json();
+            render();
+        }
+
+        load();
     </script>
 </body>
 </html>
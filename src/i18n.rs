@@ -0,0 +1,598 @@
+use std::fmt;
+
+// Languages messages are available in. English is the fallback for anything else.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    En,
+    ZhCn,
+}
+
+impl Locale {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::ZhCn => "zh-CN",
+        }
+    }
+
+    // Matches on the primary subtag, so `en-GB` is English and any `zh-*` Chinese.
+    pub fn from_tag(tag: &str) -> Option<Locale> {
+        let primary = tag.trim().split(['-', '_']).next().unwrap_or("");
+        if primary.eq_ignore_ascii_case("en") {
+            Some(Locale::En)
+        } else if primary.eq_ignore_ascii_case("zh") {
+            Some(Locale::ZhCn)
+        } else {
+            None
+        }
+    }
+
+    // A recognised `lang` parameter wins; otherwise the Accept-Language range with the
+    // highest q-value this API speaks, earlier ranges winning ties.
+    pub fn negotiate(param: Option<&str>, accept_language: &str) -> Locale {
+        if let Some(locale) = param.and_then(Locale::from_tag) {
+            return locale;
+        }
+        let mut best: Option<(Locale, f32)> = None;
+        for range in accept_language.split(',') {
+            let mut parts = range.split(';');
+            let locale = match Locale::from_tag(parts.next().unwrap_or("")) {
+                Some(locale) => locale,
+                None => continue,
+            };
+            let q = match parts.find_map(|p| p.trim().strip_prefix("q=")) {
+                Some(q) => match q.trim().parse::<f32>() {
+                    Ok(q) if (0.0..=1.0).contains(&q) => q,
+                    // A weight that does not parse says nothing about the caller's preference
+                    _ => continue,
+                },
+                None => 1.0,
+            };
+            let better = match best {
+                Some((_, b)) => q > b,
+                None => true,
+            };
+            if q > 0.0 && better {
+                best = Some((locale, q));
+            }
+        }
+        best.map_or(Locale::En, |(locale, _)| locale)
+    }
+}
+
+// A catalog key and the values for its `{}` placeholders, rendered once the caller's locale
+// is known.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Message {
+    key: &'static str,
+    args: Vec<String>,
+}
+
+impl Message {
+    pub fn new(key: &'static str, args: &[&dyn fmt::Display]) -> Self {
+        Message {
+            key,
+            args: args.iter().map(|a| a.to_string()).collect(),
+        }
+    }
+
+    pub fn render(&self, locale: Locale) -> String {
+        let template = CATALOG
+            .iter()
+            .find(|(key, _, _)| *key == self.key)
+            .map(|(_, en, zh)| match locale {
+                Locale::En => *en,
+                Locale::ZhCn => *zh,
+            })
+            .unwrap_or(self.key);
+        let mut args = self.args.iter();
+        let mut out = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(i) = rest.find("{}") {
+            out.push_str(&rest[..i]);
+            out.push_str(args.next().map_or("", String::as_str));
+            rest = &rest[i + 2..];
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+pub fn tr(locale: Locale, key: &'static str, args: &[&dyn fmt::Display]) -> String {
+    Message::new(key, args).render(locale)
+}
+
+// A tool's one-line description: its English `DESCRIPTION`, or the translation below.
+pub fn describe(locale: Locale, tool: &str, en: &'static str) -> &'static str {
+    match locale {
+        Locale::En => en,
+        Locale::ZhCn => TOOL_DESCRIPTIONS
+            .iter()
+            .find(|(name, _)| *name == tool)
+            .map_or(en, |(_, zh)| *zh),
+    }
+}
+
+// key, en, zh-CN. `{}` placeholders are filled in order.
+const CATALOG: &[(&str, &str, &str)] = &[
+    // Errors shared by every route
+    (
+        "error.malformed",
+        "Malformed request: {}",
+        "请求格式错误：{}",
+    ),
+    ("error.not_found", "Not found: {}", "未找到：{}"),
+    ("error.unknown_tool", "Unknown tool '{}'", "未知的工具 '{}'"),
+    (
+        "error.no_download",
+        "'{}' has no download",
+        "'{}' 没有可下载的文件",
+    ),
+    (
+        "error.no_stream",
+        "'{}' has no row stream",
+        "'{}' 不支持逐行输出",
+    ),
+    (
+        "error.limit_exceeded",
+        "Limit {} is {}, got {}",
+        "超出限制 {}：上限为 {}，实际为 {}",
+    ),
+    (
+        "error.rate_limited",
+        "Rate limit for '{}' exceeded, retry in {}s",
+        "'{}' 的请求过于频繁，请在 {} 秒后重试",
+    ),
+    (
+        "error.method_not_allowed",
+        "{} is not allowed on {}; use {}",
+        "{} 不能用于 {}，请使用 {}",
+    ),
+    (
+        "error.upgrade_required",
+        "{} only accepts WebSocket upgrades",
+        "{} 只接受 WebSocket 升级请求",
+    ),
+    ("error.internal", "Internal error: {}", "内部错误：{}"),
+    (
+        "error.at_most",
+        "'{}' must be at most {} (limit {}), got {}",
+        "'{}' 不能超过 {}（限制 {}），实际为 {}",
+    ),
+    (
+        "auth.missing_key",
+        "Missing API key; send `Authorization: Bearer <key>` or `X-API-Key`",
+        "缺少 API Key；请通过 `Authorization: Bearer <key>` 或 `X-API-Key` 发送",
+    ),
+    ("auth.invalid_key", "Invalid API key", "无效的 API Key"),
+    (
+        "auth.forbidden_group",
+        "This API key may not call {} tools",
+        "此 API Key 无权调用 {} 类工具",
+    ),
+    (
+        "output.cannot_render",
+        "Cannot render as {}: {}",
+        "无法输出为 {}：{}",
+    ),
+    (
+        "output.not_built",
+        "YAML and TOML output is not part of this build",
+        "此构建不包含 YAML 和 TOML 输出",
+    ),
+    (
+        "pipeline.empty",
+        "A pipeline needs at least one step",
+        "流水线至少需要一个步骤",
+    ),
+    (
+        "pipeline.no_field",
+        "Step {}: previous output has no '{}'",
+        "第 {} 步：上一步的输出中没有 '{}'",
+    ),
+    (
+        "pipeline.null",
+        "Step {}: previous step's '{}' is null",
+        "第 {} 步：上一步的 '{}' 为 null",
+    ),
+    (
+        "pipeline.needs_into",
+        "Step {}: tool '{}' needs an explicit `into`",
+        "第 {} 步：工具 '{}' 需要显式指定 `into`",
+    ),
+    (
+        "pipeline.bad_into",
+        "Step {}: cannot write to '{}' in the request",
+        "第 {} 步：无法写入请求中的 '{}'",
+    ),
+    (
+        "scaffold.archive",
+        "Unknown archive type '{}', expected 'zip' or 'tar'",
+        "未知的归档类型 '{}'，应为 'zip' 或 'tar'",
+    ),
+    (
+        "scaffold.env_key",
+        "Environment variable '{}' must start with a letter or '_' and contain only letters, digits and '_'",
+        "环境变量 '{}' 须以字母或 '_' 开头，且只能包含字母、数字和 '_'",
+    ),
+    (
+        "scaffold.env_value",
+        "Value of '{}' must not contain quotes, backslashes or line breaks",
+        "'{}' 的值不能包含引号、反斜杠或换行",
+    ),
+    (
+        "scaffold.name",
+        "Name must be 1-63 lowercase letters, digits or '-', not starting or ending with '-'",
+        "名称须为 1-63 个小写字母、数字或 '-'，且不能以 '-' 开头或结尾",
+    ),
+    (
+        "scaffold.port",
+        "Port must be between 1 and 65535",
+        "端口必须在 1 到 65535 之间",
+    ),
+    (
+        "share.not_found",
+        "Share link '{}' does not exist or has expired",
+        "分享链接 '{}' 不存在或已过期",
+    ),
+    (
+        "live.unsupported",
+        "'{}' has no live session; try one of {}",
+        "'{}' 不支持实时会话；可用的工具：{}",
+    ),
+    (
+        "live.stale",
+        "Update {} is not newer than update {}",
+        "更新 {} 不比更新 {} 新",
+    ),
+    (
+        "live.bad_edit",
+        "Edit {}..{} does not fit field '{}'",
+        "编辑范围 {}..{} 超出字段 '{}'",
+    ),
+    (
+        "mcp.invalid_request",
+        "Invalid request: expected a JSON-RPC 2.0 message",
+        "无效的请求：应为 JSON-RPC 2.0 消息",
+    ),
+    (
+        "mcp.method_not_found",
+        "Method not found: {}",
+        "方法不存在：{}",
+    ),
+    // Tool input errors
+    ("base64.invalid", "Invalid Base64: {}", "无效的 Base64：{}"),
+    (
+        "base64.not_utf8",
+        "Decoded Base64 is not valid UTF-8",
+        "Base64 解码结果不是有效的 UTF-8",
+    ),
+    (
+        "base64.unknown_action",
+        "Unknown action '{}', expected 'encode' or 'decode'",
+        "未知的操作 '{}'，应为 'encode' 或 'decode'",
+    ),
+    (
+        "case.unknown_mode",
+        "Unknown case mode '{}'",
+        "未知的命名转换模式 '{}'",
+    ),
+    (
+        "chmod.invalid",
+        "Invalid octal mode '{}', expected three digits 0-7",
+        "无效的八进制权限 '{}'，应为三位 0-7 的数字",
+    ),
+    ("color.invalid", "Invalid color: {}", "无效的颜色：{}"),
+    ("convert.failed", "Cannot convert: {}", "无法转换：{}"),
+    (
+        "cron.invalid",
+        "Invalid cron expression: {}",
+        "无效的 Cron 表达式：{}",
+    ),
+    (
+        "date.invalid",
+        "Invalid or non-existent date",
+        "无效的日期格式或不存在的日期",
+    ),
+    (
+        "escape.unknown_mode",
+        "Unknown escape mode '{}'",
+        "未知的转义模式 '{}'",
+    ),
+    ("json.invalid", "Invalid JSON: {}", "无效的 JSON：{}"),
+    ("jwt.format", "Invalid JWT format", "无效的 JWT 格式"),
+    (
+        "jwt.part_invalid",
+        "Invalid JWT {}: {}",
+        "无效的 JWT {}：{}",
+    ),
+    (
+        "jwt.part_not_utf8",
+        "JWT {} is not valid UTF-8",
+        "JWT {} 不是有效的 UTF-8",
+    ),
+    (
+        "k8s.kind",
+        "Unsupported resource kind '{}'",
+        "不支持的资源类型 '{}'",
+    ),
+    (
+        "qrcode.invalid",
+        "Cannot encode as QR code: {}",
+        "无法生成二维码：{}",
+    ),
+    ("regex.invalid", "Invalid regex: {}", "无效的正则表达式：{}"),
+    (
+        "regex.unknown_template",
+        "Unknown regex template '{}'",
+        "未知的正则模板 '{}'",
+    ),
+    (
+        "subnet.invalid",
+        "Invalid IP or CIDR '{}': {}",
+        "无效的 IP 或 CIDR '{}'：{}",
+    ),
+    (
+        "token.no_charset",
+        "At least one character class must be enabled",
+        "至少需要启用一种字符类型",
+    ),
+    ("toml.invalid", "Invalid TOML: {}", "无效的 TOML：{}"),
+    ("unit.not_a_number", "'{}' is not a number", "'{}' 不是数字"),
+    (
+        "unit.unknown_type",
+        "Unknown unit type '{}'",
+        "未知的单位类型 '{}'",
+    ),
+    (
+        "unit.unknown_unit",
+        "Unknown {} unit '{}'",
+        "未知的{}单位 '{}'",
+    ),
+    ("yaml.invalid", "Invalid YAML: {}", "无效的 YAML：{}"),
+    // Descriptions of generated commands
+    (
+        "git.undo_commit",
+        "Undo the last commit but keep its changes (soft reset)",
+        "撤销最近一次提交，但保留文件修改（Soft Reset）",
+    ),
+    (
+        "git.undo_changes",
+        "Discard all changes in the working tree (dangerous: uncommitted work is lost)",
+        "撤销工作区所有修改（危险：会丢失未提交的改动）",
+    ),
+    (
+        "git.log_graph",
+        "Show the commit history as a graph",
+        "以图形化方式查看提交历史",
+    ),
+    (
+        "git.tag",
+        "Create and push an annotated tag",
+        "创建并推送带注释的标签",
+    ),
+    (
+        "git.branch_delete",
+        "Delete the branch locally and on the remote",
+        "删除本地和远程分支",
+    ),
+    (
+        "git.stash",
+        "Stash changes, pull, then restore them",
+        "暂存修改，拉取代码，然后恢复修改",
+    ),
+    ("k8s.get", "List {} resources", "获取 {} 列表"),
+    (
+        "k8s.describe",
+        "Show details of {} {}",
+        "查看 {} {} 的详细信息",
+    ),
+    ("k8s.delete", "Delete {} {}", "删除 {} {}"),
+    ("k8s.logs", "Follow the logs of {} {}", "查看 {} {} 的日志"),
+    (
+        "k8s.exec",
+        "Open a shell in pod {} (pods only)",
+        "进入 Pod {} 的 Shell (仅适用于 Pod)",
+    ),
+    (
+        "k8s.scale",
+        "Scale {} {} to {} replicas",
+        "将 {} {} 伸缩到 {} 个副本",
+    ),
+    (
+        "k8s.port_forward",
+        "Forward {} port {} -> {}",
+        "端口转发 {} {} -> {}",
+    ),
+    (
+        "k8s.rollout_restart",
+        "Restart {} {} (rolling update)",
+        "重启 {} {} (滚动更新)",
+    ),
+    (
+        "k8s.rollout_status",
+        "Watch the rollout status of {} {}",
+        "查看 {} {} 的滚动更新状态",
+    ),
+    (
+        "k8s.rollout_history",
+        "Show the revision history of {} {}",
+        "查看 {} {} 的历史版本",
+    ),
+    (
+        "k8s.rollout_undo",
+        "Roll {} {} back to the previous revision",
+        "回滚 {} {} 到上一个版本",
+    ),
+    ("k8s.help", "Show help", "显示帮助信息"),
+    // Summaries of the extra routes in the OpenAPI document
+    (
+        "openapi.download",
+        "{} (as a file)",
+        "{}（下载为文件）",
+    ),
+    (
+        "openapi.stream",
+        "{} (streamed row by row)",
+        "{}（逐行流式输出）",
+    ),
+];
+
+// Tool name, zh-CN description.
+const TOOL_DESCRIPTIONS: &[(&str, &str)] = &[
+    ("sql", "格式化 SQL 语句"),
+    ("diff", "逐行比较两段文本"),
+    ("cron", "校验 cron 表达式并列出接下来的执行时间"),
+    ("subnet", "计算子网的网络地址、广播地址和主机范围"),
+    ("md5", "32 位和 16 位的 MD5 摘要"),
+    ("token", "由所选字符类别组成的随机令牌"),
+    ("uuid", "生成 v4 UUID"),
+    ("date", "在 Unix 时间戳和日期字符串之间转换"),
+    ("color", "将 CSS 颜色转换为 HEX、RGB、HSL 和 CMYK"),
+    ("base64", "Base64 编码或解码文本"),
+    ("js-enc", "将 JavaScript 混淆为十六进制转义的 eval"),
+    ("json", "格式化和压缩 JSON"),
+    ("url", "URL 编码、解码和解析"),
+    ("password", "生成强密码"),
+    ("qrcode", "将文本渲染为二维码 SVG"),
+    ("chmod", "根据八进制权限生成 chmod 命令"),
+    ("yaml-to-toml", "将 YAML 转换为 TOML"),
+    ("toml-to-yaml", "将 TOML 转换为 YAML"),
+    ("json-to-yaml", "将 JSON 转换为 YAML"),
+    ("jwt", "解码 JWT 的头部和载荷"),
+    ("regex", "查找正则表达式的所有匹配"),
+    ("regex-gen", "按名称查找常用正则表达式"),
+    ("escape", "转义或反转义 HTML 和 JSON 字符串"),
+    ("case", "在不同命名风格之间转换标识符"),
+    ("tar", "生成 tar 命令"),
+    ("ps", "生成 ps 命令"),
+    ("tcpdump", "生成 tcpdump 命令"),
+    ("git", "生成 git 命令"),
+    ("git-cmd", "常用 git 操作及说明"),
+    ("strace", "生成 strace 命令"),
+    ("iostat", "生成 iostat 命令"),
+    ("nice", "生成 nice 或 renice 命令"),
+    ("ls", "生成 ls 命令"),
+    ("firewall", "生成 firewall-cmd 命令"),
+    ("systemctl", "生成 systemctl 命令"),
+    ("find", "生成 find 命令"),
+    ("rsync", "生成 rsync 命令及对应的 ssh 配置"),
+    ("curl", "生成 curl 命令和 Python requests 代码"),
+    ("awk", "生成 awk 命令"),
+    ("sed", "生成 sed 命令"),
+    ("k8s-cmd", "常用 kubectl 命令及说明"),
+    ("dockerfile", "生成多阶段 Dockerfile"),
+    ("nginx", "生成 nginx server 配置块"),
+    ("k8s-yaml", "生成 Kubernetes 清单"),
+    ("ansible", "生成 Ansible playbook"),
+    ("compose", "为单个服务生成 docker-compose.yml"),
+    ("regex-build", "根据简单条件构建正则表达式"),
+    ("lorem", "Lorem ipsum 单词、句子或段落"),
+    ("fake-user", "生成虚构的用户身份"),
+    ("credit-card", "生成通过 Luhn 校验的测试卡号"),
+    ("unit-convert", "转换存储和时间单位"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn catalog_entries_are_complete() {
+        let mut keys = HashSet::new();
+        for (key, en, zh) in CATALOG {
+            assert!(keys.insert(key), "'{}' is listed twice", key);
+            assert!(!en.is_empty() && !zh.is_empty(), "'{}' is empty", key);
+            assert_eq!(
+                en.matches("{}").count(),
+                zh.matches("{}").count(),
+                "'{}' has different placeholders",
+                key
+            );
+        }
+        let mut tools = HashSet::new();
+        for (tool, zh) in TOOL_DESCRIPTIONS {
+            assert!(tools.insert(tool), "'{}' is listed twice", tool);
+            assert!(!zh.is_empty());
+        }
+    }
+
+    // Every `"<namespace>.<name>"` literal in the sources, for the namespaces the catalog
+    // uses, has to be a catalog key; a typo would otherwise render the bare key.
+    #[test]
+    fn every_key_used_in_the_sources_is_in_the_catalog() {
+        let namespaces: HashSet<&str> = CATALOG
+            .iter()
+            .filter_map(|(key, _, _)| key.split_once('.'))
+            .map(|(namespace, _)| namespace)
+            .collect();
+        // File names such as `qrcode.svg` look like keys of the same namespace
+        let extensions = ["json", "png", "svg", "txt", "yaml", "yml"];
+        let is_key = |s: &str| match s.split_once('.') {
+            Some((namespace, name)) => {
+                namespaces.contains(namespace)
+                    && !extensions.contains(&name)
+                    && !name.is_empty()
+                    && name
+                        .chars()
+                        .all(|c| c.is_ascii_lowercase() || c == '_' || c == '.')
+            }
+            None => false,
+        };
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/src");
+        let mut used = 0;
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension() != Some("rs".as_ref()) || path.ends_with("i18n.rs") {
+                continue;
+            }
+            let source = std::fs::read_to_string(&path).unwrap();
+            for literal in source.split('"').filter(|s| is_key(s)) {
+                used += 1;
+                assert!(
+                    CATALOG.iter().any(|(key, _, _)| *key == literal),
+                    "{} uses '{}', which is not in the catalog",
+                    path.display(),
+                    literal
+                );
+            }
+        }
+        assert!(used > 0);
+    }
+
+    #[test]
+    fn messages_fill_placeholders_in_order() {
+        let message = Message::new("error.limit_exceeded", &[&"MAX_BODY_BYTES", &10, &11]);
+        assert_eq!(
+            message.render(Locale::En),
+            "Limit MAX_BODY_BYTES is 10, got 11"
+        );
+        assert_eq!(
+            message.render(Locale::ZhCn),
+            "超出限制 MAX_BODY_BYTES：上限为 10，实际为 11"
+        );
+        assert_eq!(tr(Locale::En, "no.such_key", &[]), "no.such_key");
+    }
+
+    #[test]
+    fn negotiate_prefers_lang_then_the_highest_q() {
+        let negotiate = Locale::negotiate;
+        assert_eq!(negotiate(Some("zh-CN"), "en"), Locale::ZhCn);
+        assert_eq!(negotiate(Some("fr"), "zh-TW"), Locale::ZhCn);
+        assert_eq!(negotiate(None, ""), Locale::En);
+        assert_eq!(negotiate(None, "fr, zh;q=0.5, en;q=0.4"), Locale::ZhCn);
+        assert_eq!(negotiate(None, "en;q=0.5, zh;q=0.5"), Locale::En);
+        assert_eq!(negotiate(None, "zh;q=0, fr"), Locale::En);
+    }
+
+    #[test]
+    fn negotiate_ignores_unreadable_weights() {
+        let negotiate = Locale::negotiate;
+        assert_eq!(negotiate(None, "zh;q=abc, en;q=0.1"), Locale::En);
+        assert_eq!(negotiate(None, "zh;q=2, en;q=0.1"), Locale::En);
+        assert_eq!(negotiate(None, "en;q=0.1, zh;q=NaN"), Locale::En);
+        assert_eq!(negotiate(None, "zh;q=abc"), Locale::En);
+    }
+}
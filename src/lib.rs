@@ -1,378 +1,216 @@
-use serde::{Deserialize, Serialize};
 use worker::*;
 
+#[cfg(feature = "devops")]
+mod archive;
+mod auth;
+mod batch;
+mod cache;
+#[cfg(feature = "cli")]
+pub mod cli;
 mod converters;
+mod cors;
+mod error;
 mod generators;
+mod health;
 mod html;
+mod i18n;
+mod limits;
+mod live;
+mod mcp;
+// Request and response types of tools left out of the build go unused
+#[cfg_attr(
+    not(all(
+        feature = "crypto",
+        feature = "network",
+        feature = "formats",
+        feature = "devops",
+        feature = "generators",
+        feature = "text"
+    )),
+    allow(dead_code)
+)]
 mod models;
+mod openapi;
+mod output;
+mod pipeline;
+mod ratelimit;
+#[cfg(feature = "devops")]
+mod scaffold;
+mod share;
+#[cfg(feature = "generators")]
+mod stream;
 mod system;
+mod telemetry;
+mod tools;
+mod toolset;
+#[cfg(any(
+    feature = "crypto",
+    feature = "network",
+    feature = "formats",
+    feature = "devops",
+    feature = "generators",
+    feature = "text"
+))]
 mod utils;
 
+use auth::{Access, ApiKeys};
+use cors::Cors;
+use error::ToolError;
+use futures_util::StreamExt;
+use i18n::{Locale, Message};
+use limits::Limits;
 use models::*;
+use output::Format;
+use ratelimit::{Decision, MemoryLimiter, RateLimitConfig, Take};
+use serde_json::Value;
+use share::{MemoryStore, ShareRequest, ShareResponse, Shared};
+use std::cell::RefCell;
+#[cfg(feature = "generators")]
+use stream::RowFormat;
+use telemetry::{LogLine, Metrics};
+use tools::Input;
+use toolset::ToolSet;
 
+thread_local! {
+    // Stand-in for the RATE_LIMITER Durable Object when it is not bound (local dev)
+    static LOCAL_LIMITER: RefCell<MemoryLimiter> = RefCell::new(MemoryLimiter::default());
+    static METRICS: RefCell<Metrics> = RefCell::new(Metrics::default());
+    // Stand-in for the SHARES KV namespace when it is not bound (local dev)
+    static LOCAL_SHARES: RefCell<MemoryStore> = RefCell::new(MemoryStore::default());
+}
+
+// Wraps every request with an ID, a JSON log line and the metrics. Workers only advance the
+// clock across I/O, so latencies measure time spent waiting rather than CPU time.
 #[event(fetch)]
 pub async fn main(req: Request, env: Env, _ctx: worker::Context) -> Result<Response> {
-    let router = Router::new();
+    let started = Date::now().as_millis();
+    let headers = req.headers();
+    let request_id = telemetry::request_id(
+        headers.get("x-request-id")?.as_deref(),
+        headers.get("cf-ray")?.as_deref(),
+    );
+    let country = headers.get("cf-ipcountry")?;
+    let method = req.method().to_string();
+    let path = req.path();
+    let lang = req
+        .url()?
+        .query_pairs()
+        .find(|(k, _)| k == "lang")
+        .map(|(_, v)| v.into_owned());
+    let locale = Locale::negotiate(
+        lang.as_deref(),
+        &headers.get("accept-language")?.unwrap_or_default(),
+    );
 
-    router
-        .get("/", |_, _| Response::from_html(html::get_homepage()))
-        .get("/api/ping", |_, _| Response::ok("Pong"))
-        .post_async("/api/sql", |mut req, _| async move {
-            let data: SqlRequest = req.json().await?;
-            let res = utils::format_sql(&data.sql);
-            Response::from_json(&GenericResponse { result: res })
-        })
-        .post_async("/api/diff", |mut req, _| async move {
-            let data: DiffRequest = req.json().await?;
-            Response::from_json(&utils::compute_diff(&data.old, &data.new))
-        })
-        .post_async("/api/cron", |mut req, _| async move {
-            let data: CronRequest = req.json().await?;
-            Response::from_json(&utils::check_cron(&data.cron))
-        })
-        .post_async("/api/subnet", |mut req, _| async move {
-            let data: SubnetRequest = req.json().await?;
-            Response::from_json(&utils::calculate_subnet(&data.ip, data.cidr))
-        })
-        .post_async("/api/md5", |mut req, _| async move {
-            let data: Md5Request = req.json().await?;
-            Response::from_json(&utils::calculate_md5(&data.text))
-        })
-        .post_async("/api/token", |mut req, _| async move {
-            let data: TokenRequest = req.json().await?;
-            let token = utils::generate_token(
-                data.length,
-                data.uppercase,
-                data.lowercase,
-                data.numbers,
-                data.symbols,
-            );
-            Response::from_json(&TokenResponse { token })
-        })
-        .post_async("/api/uuid", |mut req, _| async move {
-            let data: UuidRequest = req.json().await?;
-            let uuids = utils::generate_uuids(UuidConfig {
-                count: data.count,
-                hyphens: data.hyphens,
-                uppercase: data.uppercase,
-            });
-            Response::from_json(&UuidResponse { uuids })
-        })
-        .post_async("/api/date", |mut req, _| async move {
-            let data: DateRequest = req.json().await?;
-            Response::from_json(&utils::parse_date(&data.input))
-        })
-        .post_async("/api/color", |mut req, _| async move {
-            let data: ColorRequest = req.json().await?;
-            Response::from_json(&utils::convert_color(&data.input))
-        })
-        .post_async("/api/base64", |mut req, _| async move {
-            let data: Base64Request = req.json().await?;
-            let res = utils::process_base64(&data.text, &data.action);
-            Response::from_json(&GenericResponse { result: res })
-        })
-        .post_async("/api/js-enc", |mut req, _| async move {
-            let data: JsEncRequest = req.json().await?;
-            let res = converters::obfuscate_js(&data.js);
-            Response::from_json(&GenericResponse { result: res })
-        })
-        .post_async("/api/json", |mut req, _| async move {
-            let data: JsonRequest = req.json().await?;
-            let (pretty, min, err) = utils::process_json(&data.input);
-            Response::from_json(&JsonResponse {
-                pretty,
-                minified: min,
-                error: err,
-            })
-        })
-        .post_async("/api/url", |mut req, _| async move {
-            let data: UrlRequest = req.json().await?;
-            let (enc, dec, protocol, host, path, params) = utils::process_url(&data.input);
-            Response::from_json(&UrlResponse {
-                encoded: enc,
-                decoded: dec,
-                protocol,
-                host,
-                path,
-                params,
-            })
-        })
-        .post_async("/api/password", |mut req, _| async move {
-            let data: PasswordRequest = req.json().await?;
-            let res = utils::generate_password_strong(
-                data.length,
-                data.uppercase,
-                data.lowercase,
-                data.numbers,
-                data.symbols,
-            );
-            Response::from_json(&PasswordResponse { password: res })
-        })
-        .post_async("/api/qrcode", |mut req, _| async move {
-            let data: QrRequest = req.json().await?;
-            match utils::generate_qr(&data.text) {
-                Ok(svg) => Response::from_json(&QrResponse { svg, error: None }),
-                Err(e) => Response::from_json(&QrResponse {
-                    svg: String::new(),
-                    error: Some(e),
-                }),
-            }
-        })
-        .post_async("/api/chmod", |mut req, _| async move {
-            let data: ChmodRequest = req.json().await?;
-            Response::from_json(&system::calculate_chmod(&data.octal, &data.file))
-        })
-        .post_async("/api/yaml-to-toml", |mut req, _| async move {
-            let data: YamlRequest = req.json().await?;
-            let (res, err) = utils::yaml_to_toml(&data.yaml);
-            let err_opt = if err.is_empty() { None } else { Some(err) };
-            Response::from_json(&YamlResponse {
-                result: res,
-                error: err_opt,
-            })
-        })
-        .post_async("/api/toml-to-yaml", |mut req, _| async move {
-            let data: TomlRequest = req.json().await?;
-            let (res, err) = utils::toml_to_yaml(&data.toml);
-            let err_opt = if err.is_empty() { None } else { Some(err) };
-            Response::from_json(&YamlResponse {
-                result: res,
-                error: err_opt,
-            })
-        })
-        .post_async("/api/jwt", |mut req, _| async move {
-            let data: JwtRequest = req.json().await?;
-            Response::from_json(&utils::parse_jwt(&data.token))
-        })
-        .post_async("/api/regex", |mut req, _| async move {
-            let data: RegexRequest = req.json().await?;
-            Response::from_json(&utils::test_regex(&data.pattern, &data.text))
-        })
-        .post_async("/api/regex-gen", |mut req, _| async move {
-            let data: RegexGenRequest = req.json().await?;
-            let pat = utils::get_common_regex(&data.key);
-            Response::from_json(&RegexPatternResponse {
-                pattern: pat.to_string(),
-            })
-        })
-        .post_async("/api/escape", |mut req, _| async move {
-            let data: EscapeRequest = req.json().await?;
-            let res = utils::process_escape(&data.text, &data.mode);
-            Response::from_json(&GenericResponse { result: res })
-        })
-        .post_async("/api/case", |mut req, _| async move {
-            let data: CaseRequest = req.json().await?;
-            let res = converters::convert_case(&data.text, &data.mode);
-            Response::from_json(&GenericResponse { result: res })
-        })
-        .post_async("/api/tar", |mut req, _| async move {
-            let data: TarRequest = req.json().await?;
-            Response::from_json(&system::generate_tar(
-                &data.op,
-                &data.comp,
-                data.verbose,
-                &data.archive,
-                &data.files,
-            ))
-        })
-        .post_async("/api/ps", |mut req, _| async move {
-            let data: PsRequest = req.json().await?;
-            Response::from_json(&system::generate_ps(
-                &data.format,
-                &data.sort,
-                data.tree,
-                &data.filter,
-                data.wide,
-                data.threads,
-                &data.user,
-                &data.pid,
-            ))
-        })
-        .post_async("/api/tcpdump", |mut req, _| async move {
-            let data: TcpdumpRequest = req.json().await?;
-            Response::from_json(&system::generate_tcpdump(
-                &data.interface,
-                &data.protocol,
-                &data.host,
-                &data.port,
-                data.verbose,
-                data.ascii,
-                data.hex,
-                &data.write_file,
-                &data.count,
-            ))
-        })
-        .post_async("/api/git", |mut req, _| async move {
-            let data: GitRequest = req.json().await?;
-            Response::from_json(&system::generate_git(
-                &data.cmd,
-                &data.target,
-                &data.msg,
-                &data.remote,
-                &data.branch,
-                data.opt_force,
-                data.opt_rebase,
-                data.opt_all,
-                data.opt_amend,
-                data.opt_hard,
-                data.opt_new_branch,
-                data.opt_tags,
-                data.opt_oneline,
-                data.opt_graph,
-            ))
-        })
-        .post_async("/api/git-cmd", |mut req, _| async move {
-            let data: GitCmdRequest = req.json().await?;
-            Response::from_json(&system::generate_git_cmd(
-                &data.action,
-                &data.tag,
-                &data.msg,
-                &data.branch,
-            ))
-        })
-        .post_async("/api/strace", |mut req, _| async move {
-            let data: StraceRequest = req.json().await?;
-            Response::from_json(&system::generate_strace(
-                &data.target,
-                data.is_pid,
-                data.follow,
-                data.summary,
-                &data.output_file,
-                &data.filter,
-                &data.string_limit,
-                data.timestamp,
-            ))
-        })
-        .post_async("/api/iostat", |mut req, _| async move {
-            let data: IostatRequest = req.json().await?;
-            Response::from_json(&system::generate_iostat(
-                &data.interval,
-                &data.count,
-                data.human,
-                data.extended,
-                &data.unit,
-                data.partitions,
-                data.timestamp,
-                &data.device,
-            ))
-        })
-        .post_async("/api/nice", |mut req, _| async move {
-            let data: NiceRequest = req.json().await?;
-            Response::from_json(&system::generate_nice(
-                &data.mode,
-                data.priority,
-                &data.command,
-                &data.target_type,
-                &data.target,
-            ))
-        })
-        .post_async("/api/ls", |mut req, _| async move {
-            let data: LsRequest = req.json().await?;
-            Response::from_json(&system::generate_ls(
-                &data.path,
-                data.all,
-                data.long,
-                data.human,
-                data.time,
-                data.reverse,
-                data.recursive,
-                data.inode,
-                data.directory,
-                data.color,
-            ))
-        })
-        .post_async("/api/firewall", |mut req, _| async move {
-            let data: FirewallRequest = req.json().await?;
-            Response::from_json(&system::generate_firewall(
-                &data.op,
-                &data.zone,
-                &data.target_type,
-                &data.target,
-                data.permanent,
-            ))
-        })
-        .post_async("/api/systemctl", |mut req, _| async move {
-            let data: SystemctlRequest = req.json().await?;
-            Response::from_json(&system::generate_systemctl(
-                &data.operation,
-                &data.service,
-                data.user_mode,
-                data.now,
-                data.force,
-                data.global,
-            ))
-        })
-        .post_async("/api/find", |mut req, _| async move {
-            let data: FindRequest = req.json().await?;
-            Response::from_json(&system::generate_find(
-                &data.path,
-                &data.name,
-                data.iname,
-                &data.target_type,
-                &data.size,
-                &data.mtime,
-                data.empty,
-                &data.exec,
-            ))
-        })
-        .post_async("/api/dockerfile", |mut req, _| async move {
-            let data: DockerfileRequest = req.json().await?;
-            Response::from_json(&GenericResponse {
-                result: system::generate_dockerfile(&data.stages),
-            })
-        })
-        .post_async("/api/nginx", |mut req, _| async move {
-            let data: NginxRequest = req.json().await?;
-            Response::from_json(&GenericResponse {
-                result: utils::generate_nginx_config(
-                    &data.domain,
-                    data.port,
-                    &data.root,
-                    &data.locations,
-                    &data.upstream,
-                    data.https,
-                    data.force_https,
-                    &data.ssl_cert,
-                    &data.ssl_key,
-                    data.gzip,
-                    &data.client_max_body_size,
-                    &data.keepalive_timeout,
-                    &data.proxy_connect_timeout,
-                    &data.proxy_read_timeout,
-                    &data.proxy_send_timeout,
-                ),
-            })
-        })
-        .post_async("/api/lorem", |mut req, _| async move {
-            let data: LoremRequest = req.json().await?;
-            Response::from_json(&GenericResponse {
-                result: generators::generate_lorem(data.count, &data.mode),
-            })
-        })
-        .post_async("/api/rsync", |mut req, _| async move {
-            let data: RsyncRequest = req.json().await?;
-            Response::from_json(&system::generate_rsync(
-                &data.source,
-                &data.user,
-                &data.host,
-                &data.port,
-                &data.remote_path,
-                data.archive,
-                data.compress,
-                data.verbose,
-                data.delete,
-                data.dry_run,
-                data.progress,
-                data.ssh,
-                &data.exclude,
-            ))
-        })
-        .post_async("/api/fake-user", |mut req, _| async move {
-            let data: FakeUserRequest = req.json().await?;
-            Response::from_json(&FakeUserResponse {
-                users: generators::generate_fake_users(data.count, &data.locale),
-            })
+    let mut resp = match handle(req, env, locale).await {
+        Ok(resp) => resp,
+        Err(e) => error_response(&ToolError::Internal(e.to_string()), locale)?,
+    };
+    resp.headers_mut().set("X-Request-Id", &request_id)?;
+    if Cors::applies(&path) {
+        resp.headers_mut()
+            .set("Content-Language", locale.as_str())?;
+    }
+
+    let status = resp.status_code();
+    let latency_ms = Date::now().as_millis().saturating_sub(started);
+    let route = telemetry::route_label(&path);
+    METRICS.with(|m| m.borrow_mut().record(route, status, latency_ms));
+    let line = LogLine {
+        level: LogLine::level(status),
+        request_id: &request_id,
+        method: &method,
+        path: &path,
+        route,
+        status,
+        latency_ms,
+        country: country.as_deref(),
+    };
+    console_log!("{}", serde_json::to_string(&line)?);
+    Ok(resp)
+}
+
+async fn handle(req: Request, env: Env, locale: Locale) -> Result<Response> {
+    let path = req.path();
+    let cors = Cors::new(
+        var(&env, "CORS_ALLOWED_ORIGINS"),
+        var(&env, "CORS_ALLOWED_METHODS"),
+        var(&env, "CORS_ALLOWED_HEADERS"),
+        var(&env, "CORS_MAX_AGE"),
+    );
+    let origin = req.headers().get("origin")?;
+    let limits = Limits::from_vars(|name| var(&env, name));
+
+    if req.method() == Method::Options && Cors::applies(&path) {
+        let mut resp = Response::empty()?.with_status(204);
+        for (name, value) in cors.headers(origin.as_deref(), true) {
+            resp.headers_mut().set(name, &value)?;
+        }
+        return Ok(resp);
+    }
+
+    let access = if auth::is_public(&path) {
+        Ok(Access::All)
+    } else {
+        ApiKeys::parse(&secret(&env, "API_KEYS").unwrap_or_default()).authenticate(
+            req.headers().get("authorization")?.as_deref(),
+            req.headers().get("x-api-key")?.as_deref(),
+        )
+    };
+    let toolset = ToolSet::new(var(&env, "ENABLED_TOOLS"), var(&env, "DISABLED_TOOLS"));
+    let ctx = tools::Context {
+        limits,
+        // Unused when authentication failed; the request is answered with the error
+        access: access
+            .as_ref()
+            .cloned()
+            .unwrap_or(Access::Groups(Vec::new())),
+        tools: toolset.clone(),
+        locale,
+    };
+
+    let mut router = Router::with_data(ctx)
+        .get("/", |_, ctx| {
+            Response::from_html(html::get_homepage(&ctx.data.tools))
         })
+        .get("/api/ping", |_, _| Response::ok("Pong"))
+        // The tools this deployment serves, so clients can discover the API
+        .get("/api/tools", |_, ctx| {
+            let tools: Vec<serde_json::Value> = ctx
+                .data
+                .tools
+                .tools()
+                .map(|t| {
+                    serde_json::json!({
+                        "name": t.name(),
+                        "description": t.description(ctx.data.locale),
+                        "path": t.path()
+                    })
+                })
+                .collect();
+            Response::from_json(&tools)
+        })
+        .get("/api/openapi.json", |_, ctx| {
+            Response::from_json(&openapi::document(&ctx.data.tools, ctx.data.locale))
+        })
+        .get("/api/health", |_, ctx| {
+            Response::from_json(&health::health(&ctx.data.tools))
+        })
+        .get("/api/selftest", |_, ctx| {
+            let report = health::selftest(&ctx.data.tools);
+            let status = if report.ok { 200 } else { 503 };
+            Ok(Response::from_json(&report)?.with_status(status))
+        })
+        .get("/api/metrics", |_, _| {
+            let mut resp = Response::ok(METRICS.with(|m| m.borrow().render()))?;
+            resp.headers_mut()
+                .set("Content-Type", "text/plain; version=0.0.4")?;
+            Ok(resp)
+        })
+        .get(
+            "/api/docs",
+            |_, _| Response::from_html(html::get_api_docs()),
+        )
         .post_async("/api/whoami", |req, _| async move {
             let headers = req.headers();
             let ip = headers
@@ -415,554 +253,545 @@ pub async fn main(req: Request, env: Env, _ctx: worker::Context) -> Result<Respo
                 headers: header_map,
             })
         })
-        .post_async("/api/unit-convert", |mut req, _| async move {
-            let data: UnitRequest = req.json().await?;
-            let val = data.value.parse::<f64>().unwrap_or(0.0);
-            Response::from_json(&converters::convert_unit(
-                val,
-                &data.type_,
-                &data.from,
-                &data.to,
-            ))
-        })
-        .post_async("/api/curl", |mut req, _| async move {
-            let data: CurlRequest = req.json().await?;
-            Response::from_json(&system::generate_curl(
-                &data.method,
-                &data.url,
-                &data.headers,
-                &data.body,
-            ))
-        })
-        .post_async("/api/credit-card", |mut req, _| async move {
-            let data: CreditCardRequest = req.json().await?;
-            Response::from_json(&CreditCardResponse {
-                cards: generators::generate_credit_cards(data.count, &data.issuer),
-            })
-        })
-        .post_async("/api/awk", |mut req, _| async move {
-            let data: AwkRequest = req.json().await?;
-            Response::from_json(&system::generate_awk(
-                &data.separator,
-                &data.variable,
-                &data.code,
-                &data.file,
-            ))
-        })
-        .post_async("/api/sed", |mut req, _| async move {
-            let data: SedRequest = req.json().await?;
-            Response::from_json(&system::generate_sed(
-                &data.operation,
-                &data.pattern,
-                &data.replacement,
-                &data.flags,
-                data.inplace,
-                &data.file,
-            ))
-        })
-        .post_async("/api/regex-build", |mut req, _| async move {
-            let data: RegexBuildRequest = req.json().await?;
-            Response::from_json(&generators::generate_custom_regex(
-                &data.starts_with,
-                &data.not_starts_with,
-                &data.ends_with,
-                &data.not_ends_with,
-                &data.contains,
-                &data.not_contains,
-            ))
-        })
-        .post_async("/api/k8s-yaml", |mut req, _| async move {
-            let data: K8sRequest = req.json().await?;
-            let yaml = generate_k8s_yaml(&data);
-            Response::from_json(&GenericResponse { result: yaml })
-        })
-        .post_async("/api/k8s-cmd", |mut req, _| async move {
-            let data: K8sCmdRequest = req.json().await?;
-            let (cmd, desc) = generate_k8s_cmd(&data);
-            Response::from_json(&K8sCmdResponse {
-                command: cmd,
-                description: desc,
-            })
-        })
-        .post_async("/api/ansible", |mut req, _| async move {
-            let data: AnsibleRequest = req.json().await?;
-            let yaml = generate_ansible_yaml(&data);
-            Response::from_json(&GenericResponse { result: yaml })
-        })
-        .run(req, env)
-        .await
-}
+        .post_async("/api/batch", run_batch)
+        .post_async("/api/pipeline", run_pipeline)
+        .post_async("/api/mcp", run_mcp)
+        .post_async("/api/share", run_share)
+        .get_async("/api/share/:id", get_share)
+        .get("/s/:id", open_share)
+        .get("/api/live/:tool", open_live)
+        // No server-initiated stream to open, as the MCP transport allows
+        .get("/api/mcp", |_, ctx| {
+            let e = ToolError::MethodNotAllowed(Message::new(
+                "error.method_not_allowed",
+                &[&"GET", &"/api/mcp", &"POST"],
+            ));
+            let mut resp = error_response(&e, ctx.data.locale)?;
+            resp.headers_mut().set("Allow", "POST")?;
+            Ok(resp)
+        })
+        .or_else_any_method("/api/*path", |req, ctx| {
+            error_response(&not_found(&req.path()), ctx.data.locale)
+        });
 
-#[derive(Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-struct K8sRequest {
-    #[serde(default = "default_kind")]
-    kind: String,
-    #[serde(default = "default_name")]
-    name: String,
-    #[serde(default = "default_namespace")]
-    namespace: String,
-    #[serde(default = "default_image")]
-    image: String,
-    #[serde(default = "default_replicas")]
-    replicas: i32,
-    #[serde(default = "default_port")]
-    port: i32,
-    #[serde(default = "default_target_port")]
-    target_port: i32,
-    #[serde(default = "default_service_type")]
-    service_type: String,
-    #[serde(default = "default_ingress_host")]
-    ingress_host: String,
-    #[serde(default = "default_ingress_path")]
-    ingress_path: String,
-    #[serde(default = "default_pull_policy")]
-    pull_policy: String,
-    cpu_limit: Option<String>,
-    memory_limit: Option<String>,
-    cpu_request: Option<String>,
-    memory_request: Option<String>,
-    #[serde(default)]
-    env: Vec<K8sEnvVar>,
-    #[serde(default = "default_schedule")]
-    schedule: String,
-    #[serde(default = "default_restart_policy")]
-    restart_policy: String,
-}
+    #[cfg(feature = "devops")]
+    {
+        router = router
+            .get_async("/api/scaffold", run_scaffold)
+            .post_async("/api/scaffold", run_scaffold);
+    }
 
-#[derive(Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-struct K8sCmdRequest {
-    action: String,
-    #[serde(default = "default_namespace")]
-    namespace: String,
-    #[serde(default = "default_resource_type")]
-    resource_type: String,
-    #[serde(default)]
-    resource_name: String,
-    #[serde(default)]
-    replicas: i32,
-    #[serde(default)]
-    local_port: i32,
-    #[serde(default)]
-    remote_port: i32,
-    #[serde(default)]
-    output_format: String,
-}
+    // Disabled tools get no route and fall through to the 404 above
+    for tool in toolset.tools() {
+        router = router
+            .get_async(&tool.path(), run_tool)
+            .post_async(&tool.path(), run_tool);
+        if tool.default_filename().is_some() {
+            let download = format!("{}/download", tool.path());
+            router = router
+                .get_async(&download, run_download)
+                .post_async(&download, run_download);
+        }
+        // Only generators stream
+        #[cfg(feature = "generators")]
+        if tool.streams() {
+            let stream = format!("{}/stream", tool.path());
+            router = router
+                .get_async(&stream, run_stream)
+                .post_async(&stream, run_stream);
+        }
+    }
 
-#[derive(Serialize)]
-struct K8sCmdResponse {
-    command: String,
-    description: String,
+    // Reject oversized bodies before reading them; parsing checks the actual size again
+    let declared = req
+        .headers()
+        .get("content-length")?
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    let decision = if Cors::applies(&path) {
+        rate_limit(&req, &env, &path).await?
+    } else {
+        None
+    };
+    let mut resp = match decision {
+        Some(d) if !d.allowed => error_response(
+            &ToolError::RateLimited {
+                route: ratelimit::route(&path).to_string(),
+                retry_after: d.retry_after,
+            },
+            locale,
+        )?,
+        _ => match access.and(limits.body(declared)) {
+            Ok(()) => router.run(req, env).await?,
+            Err(e) => error_response(&e, locale)?,
+        },
+    };
+    for (name, value) in decision.iter().flat_map(Decision::headers) {
+        resp.headers_mut().set(name, &value)?;
+    }
+    if Cors::applies(&path) {
+        for (name, value) in cors.headers(origin.as_deref(), false) {
+            // Tool responses already vary on Accept and Accept-Language
+            if name == "Vary" {
+                resp.headers_mut().append(name, &value)?;
+            } else {
+                resp.headers_mut().set(name, &value)?;
+            }
+        }
+    }
+    Ok(resp)
 }
 
-#[derive(Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-struct AnsibleRequest {
-    #[serde(default = "default_play_name")]
-    play_name: String,
-    #[serde(default = "default_hosts")]
-    hosts: String,
-    #[serde(default)]
-    r#become: bool,
-    #[serde(default = "default_true")]
-    gather_facts: bool,
-    #[serde(default)]
-    vars: String,
-    #[serde(default)]
-    tasks: String,
-    #[serde(default)]
-    handlers: String,
+fn var(env: &Env, name: &str) -> Option<String> {
+    env.var(name).ok().map(|v| v.to_string())
 }
 
-#[derive(Deserialize, Serialize)]
-struct K8sEnvVar {
-    key: String,
-    value: String,
+fn secret(env: &Env, name: &str) -> Option<String> {
+    env.secret(name).ok().map(|v| v.to_string())
 }
 
-fn default_kind() -> String {
-    "Deployment".to_string()
+// Takes a token from the caller's bucket for this route. `None` when the route has no budget
+// or the caller is allowlisted.
+async fn rate_limit(req: &Request, env: &Env, path: &str) -> Result<Option<Decision>> {
+    take_token(env, &client_ip(req)?, ratelimit::route(path)).await
 }
-fn default_name() -> String {
-    "app-name".to_string()
-}
-fn default_namespace() -> String {
-    "default".to_string()
-}
-fn default_image() -> String {
-    "nginx:latest".to_string()
-}
-fn default_replicas() -> i32 {
-    1
-}
-fn default_port() -> i32 {
-    80
-}
-fn default_target_port() -> i32 {
-    80
-}
-fn default_service_type() -> String {
-    "ClusterIP".to_string()
-}
-fn default_ingress_host() -> String {
-    "example.com".to_string()
-}
-fn default_ingress_path() -> String {
-    "/".to_string()
-}
-fn default_pull_policy() -> String {
-    "IfNotPresent".to_string()
-}
-fn default_schedule() -> String {
-    "*/1 * * * *".to_string()
+
+fn client_ip(req: &Request) -> Result<String> {
+    Ok(req
+        .headers()
+        .get("cf-connecting-ip")?
+        .unwrap_or_else(|| "local".into()))
 }
-fn default_restart_policy() -> String {
-    "Always".to_string()
+
+// A token from `tool`'s bucket for a call dispatched by a batch, pipeline, MCP request or
+// live session, refused with the same error as a direct call.
+async fn charge(env: &Env, ip: &str, tool: &str) -> std::result::Result<(), ToolError> {
+    match take_token(env, ip, tool).await {
+        Ok(Some(d)) if !d.allowed => Err(ToolError::RateLimited {
+            route: tool.to_string(),
+            retry_after: d.retry_after,
+        }),
+        Ok(_) => Ok(()),
+        Err(e) => Err(ToolError::Internal(e.to_string())),
+    }
 }
-fn default_play_name() -> String {
-    "Ansible Playbook".to_string()
+
+async fn take_token(env: &Env, ip: &str, route: &str) -> Result<Option<Decision>> {
+    let config = RateLimitConfig::new(
+        var(env, "RATE_LIMIT_DEFAULT"),
+        var(env, "RATE_LIMIT_ROUTES"),
+        var(env, "RATE_LIMIT_ALLOWLIST"),
+    );
+    let budget = match config.budget(route) {
+        Some(budget) => budget,
+        None => return Ok(None),
+    };
+    if config.is_allowlisted(ip) {
+        return Ok(None);
+    }
+
+    match env.durable_object("RATE_LIMITER") {
+        // One object per client, holding a bucket per route
+        Ok(namespace) => {
+            let take = Take {
+                key: route.to_string(),
+                budget,
+            };
+            let mut init = RequestInit::new();
+            init.with_method(Method::Post)
+                .with_body(Some(serde_json::to_string(&take)?.into()));
+            let stub = namespace.id_from_name(ip)?.get_stub()?;
+            let mut resp = stub
+                .fetch_with_request(Request::new_with_init("https://rate-limiter/take", &init)?)
+                .await?;
+            Ok(Some(resp.json().await?))
+        }
+        Err(_) => {
+            let take = Take {
+                key: format!("{}|{}", ip, route),
+                budget,
+            };
+            let now = Date::now().as_millis();
+            Ok(Some(
+                LOCAL_LIMITER.with(|limiter| limiter.borrow_mut().take(&take, now)),
+            ))
+        }
+    }
 }
-fn default_hosts() -> String {
-    "all".to_string()
+
+// Token buckets for one client (the object name is its IP), kept in the object's memory.
+// An evicted object starts over with full buckets, which errs on the side of the client.
+#[durable_object]
+pub struct RateLimiter {
+    limiter: RefCell<MemoryLimiter>,
 }
-fn default_resource_type() -> String {
-    "pod".to_string()
+
+impl DurableObject for RateLimiter {
+    fn new(_state: State, _env: Env) -> Self {
+        RateLimiter {
+            limiter: RefCell::new(MemoryLimiter::default()),
+        }
+    }
+
+    async fn fetch(&self, mut req: Request) -> Result<Response> {
+        let take: Take = req.json().await?;
+        let decision = self
+            .limiter
+            .borrow_mut()
+            .take(&take, Date::now().as_millis());
+        Response::from_json(&decision)
+    }
 }
-fn default_true() -> bool {
-    true
+
+async fn run_tool(mut req: Request, ctx: RouteContext<tools::Context>) -> Result<Response> {
+    let path = req.path();
+    let name = path.trim_start_matches("/api/");
+    let tool = match tools::find(name) {
+        Some(tool) => tool,
+        None => return error_response(&not_found(&path), ctx.data.locale),
+    };
+    let input = read_input(&mut req).await?;
+    let request = tool.pure_request(&input, &ctx.data.limits);
+    let pure = request.is_some();
+    let result = match request {
+        Some(request) => cached_call(&req, &ctx, tool, &request, input).await?,
+        None => tool.call(input, &ctx.data),
+    };
+    match result {
+        Ok(output) => tool_response(&req, tool, &output, pure, ctx.data.locale),
+        Err(e) => error_response(&e, ctx.data.locale),
+    }
 }
 
-fn generate_k8s_yaml(data: &K8sRequest) -> String {
-    let mut yaml = String::new();
-
-    if data.kind == "Deployment" {
-        yaml.push_str(&format!(
-            r#"apiVersion: apps/v1
-kind: Deployment
-metadata:
-  name: {}
-  namespace: {}
-  labels:
-    app: {}
-spec:
-  replicas: {}
-  selector:
-    matchLabels:
-      app: {}
-  template:
-    metadata:
-      labels:
-        app: {}
-    spec:
-      containers:
-      - name: {}
-        image: {}
-        imagePullPolicy: {}
-        ports:
-        - containerPort: {}
-"#,
-            data.name,
-            data.namespace,
-            data.name,
-            data.replicas,
-            data.name,
-            data.name,
-            data.name,
-            data.image,
-            data.pull_policy,
-            data.port
-        ));
-
-        if data.cpu_limit.is_some()
-            || data.memory_limit.is_some()
-            || data.cpu_request.is_some()
-            || data.memory_request.is_some()
-        {
-            yaml.push_str("        resources:\n");
-            if data.cpu_limit.is_some() || data.memory_limit.is_some() {
-                yaml.push_str("          limits:\n");
-                if let Some(ref cpu) = data.cpu_limit {
-                    yaml.push_str(&format!("            cpu: {}\n", cpu));
-                }
-                if let Some(ref mem) = data.memory_limit {
-                    yaml.push_str(&format!("            memory: {}\n", mem));
-                }
-            }
-            if data.cpu_request.is_some() || data.memory_request.is_some() {
-                yaml.push_str("          requests:\n");
-                if let Some(ref cpu) = data.cpu_request {
-                    yaml.push_str(&format!("            cpu: {}\n", cpu));
-                }
-                if let Some(ref mem) = data.memory_request {
-                    yaml.push_str(&format!("            memory: {}\n", mem));
-                }
-            }
-        }
+// Results of pure tools go through the Cache API when CACHE_TTL is set, keyed by tool, locale
+// and the parsed request. Errors are not stored.
+async fn cached_call(
+    req: &Request,
+    ctx: &RouteContext<tools::Context>,
+    tool: &dyn tools::DynTool,
+    request: &Value,
+    input: Input,
+) -> Result<std::result::Result<Value, ToolError>> {
+    let ttl = cache::ttl(var(&ctx.env, "CACHE_TTL").as_deref());
+    if ttl == 0 {
+        return Ok(tool.call(input, &ctx.data));
+    }
+    let key = cache::key(tool.name(), ctx.data.locale, request);
+    // A hit skips `call`, which is where API key scopes are otherwise enforced
+    if let Err(e) = ctx.data.access.check(tool.group()) {
+        return Ok(Err(e));
+    }
+    let mut url = req.url()?;
+    url.set_path(&format!("/__cache/{}/{}", tool.name(), key));
+    url.set_query(None);
+    let cache = Cache::default();
+    if let Some(mut hit) = cache.get(url.as_str(), false).await? {
+        return Ok(Ok(hit.json().await?));
+    }
+    let result = tool.call(input, &ctx.data);
+    if let Ok(output) = &result {
+        let mut entry = Response::from_json(output)?;
+        entry
+            .headers_mut()
+            .set("Cache-Control", &format!("max-age={}", ttl))?;
+        cache.put(url.as_str(), entry).await?;
+    }
+    Ok(result)
+}
 
-        if !data.env.is_empty() {
-            yaml.push_str("        env:\n");
-            for e in &data.env {
-                if !e.key.is_empty() && !e.value.is_empty() {
-                    yaml.push_str(&format!(
-                        "        - name: {}\n          value: \"{}\"\n",
-                        e.key, e.value
-                    ));
-                }
-            }
-        }
+// GET routes read the query string, everything else the JSON body.
+async fn read_input(req: &mut Request) -> Result<Input> {
+    if req.method() == Method::Get {
+        Ok(Input::Query(
+            req.url()?.query().unwrap_or_default().to_string(),
+        ))
+    } else {
+        Ok(Input::Body(req.text().await?))
+    }
+}
 
-        yaml.push_str(&format!("      restartPolicy: {}", data.restart_policy));
-    } else if data.kind == "Service" {
-        yaml.push_str(&format!(
-            r#"apiVersion: v1
-kind: Service
-metadata:
-  name: {}
-  namespace: {}
-  labels:
-    app: {}
-spec:
-  type: {}
-  selector:
-    app: {}
-  ports:
-  - protocol: TCP
-    port: {}
-    targetPort: {}
-"#,
-            data.name,
-            data.namespace,
-            data.name,
-            data.service_type,
-            data.name,
-            data.port,
-            data.target_port
-        ));
-    } else if data.kind == "Ingress" {
-        yaml.push_str(&format!(
-            r#"apiVersion: networking.k8s.io/v1
-kind: Ingress
-metadata:
-  name: {}
-  namespace: {}
-  annotations:
-    nginx.ingress.kubernetes.io/rewrite-target: /
-spec:
-  rules:
-  - host: {}
-    http:
-      paths:
-      - path: {}
-        pathType: Prefix
-        backend:
-          service:
-            name: {}
-            port:
-              number: {}
-"#,
-            data.name, data.namespace, data.ingress_host, data.ingress_path, data.name, data.port
-        ));
-    } else if data.kind == "CronJob" {
-        yaml.push_str(&format!(
-            r#"apiVersion: batch/v1
-kind: CronJob
-metadata:
-  name: {}
-  namespace: {}
-spec:
-  schedule: "{}"
-  jobTemplate:
-    spec:
-      template:
-        spec:
-          containers:
-          - name: {}
-            image: {}
-            imagePullPolicy: {}
-            command:
-            - /bin/sh
-            - -c
-            - "echo Hello Kubernetes"
-          restartPolicy: OnFailure
-"#,
-            data.name, data.namespace, data.schedule, data.name, data.image, data.pull_policy
-        ));
-    } else if data.kind == "ConfigMap" {
-        yaml.push_str(&format!(
-            r#"apiVersion: v1
-kind: ConfigMap
-metadata:
-  name: {}
-  namespace: {}
-data:
-"#,
-            data.name, data.namespace
-        ));
-        if !data.env.is_empty() {
-            for e in &data.env {
-                if !e.key.is_empty() && !e.value.is_empty() {
-                    yaml.push_str(&format!("  {}: \"{}\"\n", e.key, e.value));
-                }
-            }
-        } else {
-            yaml.push_str("  config.json: |\n    {\n      \"key\": \"value\"\n    }");
+// JSON by default; `?format=` or the Accept header picks YAML, TOML, plain text or the raw
+// generated document. Pure tools send an ETag and answer a matching If-None-Match with 304.
+fn tool_response(
+    req: &Request,
+    tool: &dyn tools::DynTool,
+    output: &Value,
+    pure: bool,
+    locale: Locale,
+) -> Result<Response> {
+    let param = req
+        .url()?
+        .query_pairs()
+        .find(|(k, _)| k == "format")
+        .map(|(_, v)| v.into_owned());
+    let accept = req.headers().get("accept")?.unwrap_or_default();
+    let format = Format::negotiate(param.as_deref(), &accept, tool.media_type());
+    match output::render(output, format, tool.media_type()) {
+        Ok(rendered) if pure => {
+            let etag = cache::etag(rendered.content_type, &rendered.body);
+            let fresh = req
+                .headers()
+                .get("if-none-match")?
+                .is_some_and(|v| cache::matches(&v, &etag));
+            let mut resp = if fresh {
+                Response::empty()?.with_status(304)
+            } else {
+                Response::ok(rendered.body)?
+            };
+            let headers = resp.headers_mut();
+            headers.set("Content-Type", rendered.content_type)?;
+            headers.set("ETag", &etag)?;
+            // Clients may keep the result but should revalidate it
+            headers.set("Cache-Control", "no-cache")?;
+            headers.set("Vary", "Accept, Accept-Language")?;
+            Ok(resp)
         }
-    } else if data.kind == "Secret" {
-        yaml.push_str(&format!(
-            r#"apiVersion: v1
-kind: Secret
-metadata:
-  name: {}
-  namespace: {}
-type: Opaque
-data:
-  # Data should be base64 encoded
-"#,
-            data.name, data.namespace
-        ));
-        if !data.env.is_empty() {
-            use base64::{engine::general_purpose, Engine as _};
-            for e in &data.env {
-                if !e.key.is_empty() && !e.value.is_empty() {
-                    let b64 = general_purpose::STANDARD.encode(&e.value);
-                    yaml.push_str(&format!("  {}: {}\n", e.key, b64));
-                }
-            }
-        } else {
-            yaml.push_str("  username: YWRtaW4=");
+        Ok(rendered) => {
+            let mut resp = Response::ok(rendered.body)?;
+            resp.headers_mut()
+                .set("Content-Type", rendered.content_type)?;
+            Ok(resp)
         }
+        Err(e) => error_response(&e, locale),
     }
+}
 
-    yaml
+// Serves a generator's document as a file, so `curl -OJ` saves it under its usual name.
+async fn run_download(mut req: Request, ctx: RouteContext<tools::Context>) -> Result<Response> {
+    let path = req.path();
+    let name = path
+        .trim_start_matches("/api/")
+        .trim_end_matches("/download");
+    let tool = match tools::find(name) {
+        Some(tool) => tool,
+        None => return error_response(&not_found(&path), ctx.data.locale),
+    };
+    let result = tool.download(read_input(&mut req).await?, &ctx.data);
+    match result {
+        Ok(file) => attachment(Response::ok(file.body)?, file.media_type, &file.filename),
+        Err(e) => error_response(&e, ctx.data.locale),
+    }
 }
 
-fn generate_k8s_cmd(data: &K8sCmdRequest) -> (String, String) {
-    let ns = if data.namespace.is_empty() {
-        "default"
-    } else {
-        &data.namespace
+// Writes a generator's rows as NDJSON or CSV (`?format=` or Accept) while they are generated,
+// so large counts never sit in memory at once.
+#[cfg(feature = "generators")]
+async fn run_stream(mut req: Request, ctx: RouteContext<tools::Context>) -> Result<Response> {
+    let path = req.path();
+    let name = path.trim_start_matches("/api/").trim_end_matches("/stream");
+    let tool = match tools::find(name) {
+        Some(tool) => tool,
+        None => return error_response(&not_found(&path), ctx.data.locale),
     };
-    let name = if data.resource_name.is_empty() {
-        "app"
-    } else {
-        &data.resource_name
+    let rows = match tool.stream(read_input(&mut req).await?, &ctx.data) {
+        Ok(rows) => rows,
+        Err(e) => return error_response(&e, ctx.data.locale),
     };
-    let rtype = if data.resource_type.is_empty() {
-        "pod"
-    } else {
-        &data.resource_type
+    let param = req
+        .url()?
+        .query_pairs()
+        .find(|(k, _)| k == "format")
+        .map(|(_, v)| v.into_owned());
+    let accept = req.headers().get("accept")?.unwrap_or_default();
+    let format = RowFormat::negotiate(param.as_deref(), &accept);
+    let seed = rows.seed;
+    let chunks = stream::chunks(rows, format).map(Ok::<String, Error>);
+    let mut resp = Response::from_stream(futures_util::stream::iter(chunks))?;
+    let headers = resp.headers_mut();
+    headers.set("Content-Type", format.content_type())?;
+    // The body has no room for it, and it is known before the first row
+    if let Some(seed) = seed {
+        headers.set("X-Seed", &seed.to_string())?;
+    }
+    Ok(resp)
+}
+
+#[cfg(feature = "devops")]
+async fn run_scaffold(mut req: Request, ctx: RouteContext<tools::Context>) -> Result<Response> {
+    if let Err(e) = ctx.data.access.check(tools::Group::Devops) {
+        return error_response(&e, ctx.data.locale);
+    }
+    let data: scaffold::ScaffoldRequest = match read_input(&mut req).await?.parse(&ctx.data.limits)
+    {
+        Ok(data) => data,
+        Err(e) => return error_response(&e, ctx.data.locale),
     };
+    match scaffold::build(&data) {
+        Ok(bundle) => attachment(
+            Response::from_bytes(bundle.bytes)?,
+            bundle.media_type,
+            &bundle.filename,
+        ),
+        Err(e) => error_response(&e, ctx.data.locale),
+    }
+}
 
-    let output = if data.output_format.is_empty() {
-        String::new()
-    } else {
-        format!(" -o {}", data.output_format)
+fn attachment(mut resp: Response, media_type: &str, filename: &str) -> Result<Response> {
+    let headers = resp.headers_mut();
+    headers.set("Content-Type", media_type)?;
+    headers.set(
+        "Content-Disposition",
+        &format!("attachment; filename=\"{}\"", filename),
+    )?;
+    Ok(resp)
+}
+
+async fn run_batch(mut req: Request, ctx: RouteContext<tools::Context>) -> Result<Response> {
+    let ip = client_ip(&req)?;
+    let items = match Input::Body(req.text().await?).parse(&ctx.data.limits) {
+        Ok(items) => items,
+        Err(e) => return error_response(&e, ctx.data.locale),
     };
+    let env = &ctx.env;
+    match batch::run(items, &ctx.data, |tool| charge(env, &ip, tool)).await {
+        Ok(output) => Response::from_json(&output),
+        Err(e) => error_response(&e, ctx.data.locale),
+    }
+}
 
-    match data.action.as_str() {
-        "get" => (
-            format!("kubectl get {} -n {}{}", rtype, ns, output),
-            format!("获取 {} 列表", rtype),
-        ),
-        "describe" => (
-            format!("kubectl describe {} {} -n {}", rtype, name, ns),
-            format!("查看 {} {} 的详细信息", rtype, name),
-        ),
-        "delete" => (
-            format!("kubectl delete {} {} -n {}", rtype, name, ns),
-            format!("删除 {} {}", rtype, name),
-        ),
-        "logs" => {
-            let target = if rtype == "pod" {
-                name.to_string()
-            } else {
-                format!("{}/{}", rtype, name)
-            };
-            (
-                format!("kubectl logs -f {} -n {}", target, ns),
-                format!("查看 {} {} 的日志", rtype, name),
-            )
-        }
-        "exec" => (
-            format!("kubectl exec -it {} -n {} -- /bin/sh", name, ns),
-            format!("进入 Pod {} 的 Shell (仅适用于 Pod)", name),
-        ),
-        "scale" => (
-            format!(
-                "kubectl scale {} {} --replicas={} -n {}",
-                rtype, name, data.replicas, ns
-            ),
-            format!("将 {} {} 伸缩到 {} 个副本", rtype, name, data.replicas),
-        ),
-        "port_forward" => (
-            format!(
-                "kubectl port-forward {} {}:{} -n {}",
-                if rtype == "pod" {
-                    name.to_string()
-                } else {
-                    format!("{}/{}", rtype, name)
-                },
-                data.local_port,
-                data.remote_port,
-                ns
-            ),
-            format!(
-                "端口转发 {} {} -> {}",
-                rtype, data.local_port, data.remote_port
-            ),
-        ),
-        "rollout_restart" => (
-            format!("kubectl rollout restart {} {} -n {}", rtype, name, ns),
-            format!("重启 {} {} (滚动更新)", rtype, name),
-        ),
-        "rollout_status" => (
-            format!("kubectl rollout status {} {} -n {}", rtype, name, ns),
-            format!("查看 {} {} 的滚动更新状态", rtype, name),
-        ),
-        "rollout_history" => (
-            format!("kubectl rollout history {} {} -n {}", rtype, name, ns),
-            format!("查看 {} {} 的历史版本", rtype, name),
-        ),
-        "rollout_undo" => (
-            format!("kubectl rollout undo {} {} -n {}", rtype, name, ns),
-            format!("回滚 {} {} 到上一个版本", rtype, name),
-        ),
-        _ => ("kubectl --help".to_string(), "显示帮助信息".to_string()),
+async fn run_pipeline(mut req: Request, ctx: RouteContext<tools::Context>) -> Result<Response> {
+    let ip = client_ip(&req)?;
+    let pipeline = match Input::Body(req.text().await?).parse(&ctx.data.limits) {
+        Ok(pipeline) => pipeline,
+        Err(e) => return error_response(&e, ctx.data.locale),
+    };
+    let env = &ctx.env;
+    match pipeline::run(pipeline, &ctx.data, |tool| charge(env, &ip, tool)).await {
+        Ok(output) => Ok(Response::from_json(&output)?.with_status(output.status())),
+        Err(e) => error_response(&e, ctx.data.locale),
     }
 }
 
-fn generate_ansible_yaml(data: &AnsibleRequest) -> String {
-    let mut yaml = String::new();
-    yaml.push_str(&format!("- name: {}\n", data.play_name));
-    yaml.push_str(&format!("  hosts: {}\n", data.hosts));
-    if data.r#become {
-        yaml.push_str("  become: yes\n");
+// Saves a tool and its input under a short ID, in KV with an expiration TTL.
+async fn run_share(mut req: Request, ctx: RouteContext<tools::Context>) -> Result<Response> {
+    let request: ShareRequest = match Input::Body(req.text().await?).parse(&ctx.data.limits) {
+        Ok(request) => request,
+        Err(e) => return error_response(&e, ctx.data.locale),
+    };
+    let (shared, ttl) = match request.into_shared(&ctx.data, now_secs()) {
+        Ok(shared) => shared,
+        Err(e) => return error_response(&e, ctx.data.locale),
+    };
+    let id = share::new_id();
+    let value = serde_json::to_string(&shared)?;
+    match ctx.env.kv("SHARES") {
+        Ok(kv) => kv.put(&id, value)?.expiration_ttl(ttl).execute().await?,
+        Err(_) => LOCAL_SHARES.with(|store| store.borrow_mut().put(&id, value, shared.expires_at)),
     }
-    if !data.gather_facts {
-        yaml.push_str("  gather_facts: no\n");
+    Ok(Response::from_json(&ShareResponse::new(id, &shared))?.with_status(201))
+}
+
+async fn get_share(_req: Request, ctx: RouteContext<tools::Context>) -> Result<Response> {
+    let id = ctx.param("id").cloned().unwrap_or_default();
+    match load_share(&ctx.env, &id).await? {
+        Some(shared) => Response::from_json(&shared),
+        None => error_response(
+            &ToolError::NotFound(Message::new("share.not_found", &[&id])),
+            ctx.data.locale,
+        ),
     }
-    if !data.vars.trim().is_empty() {
-        yaml.push_str("  vars:\n");
-        for line in data.vars.lines() {
-            yaml.push_str(&format!("    {}\n", line));
-        }
+}
+
+// The homepage reads `#s=<id>` back through /api/share/:id, fills in the tool and reports
+// links that have expired.
+fn open_share(req: Request, ctx: RouteContext<tools::Context>) -> Result<Response> {
+    let id = ctx.param("id").cloned().unwrap_or_default();
+    let mut url = req.url()?;
+    url.set_path("/");
+    url.set_query(None);
+    url.set_fragment(Some(&format!("s={}", id)));
+    Response::redirect(url)
+}
+
+async fn load_share(env: &Env, id: &str) -> Result<Option<Shared>> {
+    if !share::is_valid_id(id) {
+        return Ok(None);
     }
-    yaml.push_str("  tasks:\n");
+    let value = match env.kv("SHARES") {
+        Ok(kv) => kv.get(id).text().await?,
+        Err(_) => LOCAL_SHARES.with(|store| store.borrow_mut().get(id, now_secs())),
+    };
+    Ok(value.and_then(|v| serde_json::from_str(&v).ok()))
+}
 
-    if data.tasks.trim().is_empty() {
-        yaml.push_str("    - name: Ping hosts\n      ping:\n");
-    } else {
-        for line in data.tasks.lines() {
-            yaml.push_str(&format!("    {}\n", line));
-        }
+fn now_secs() -> u64 {
+    Date::now().as_millis() / 1000
+}
+
+// JSON-RPC 2.0 in MCP's message shapes. A body of only notifications is acknowledged with
+// 202 and no content.
+async fn run_mcp(mut req: Request, ctx: RouteContext<tools::Context>) -> Result<Response> {
+    let ip = client_ip(&req)?;
+    let body = req.text().await?;
+    let env = &ctx.env;
+    match mcp::handle(&body, &ctx.data, |tool| charge(env, &ip, tool)).await {
+        Ok(Some(reply)) => Response::from_json(&reply),
+        Ok(None) => Ok(Response::empty()?.with_status(202)),
+        Err(e) => error_response(&e, ctx.data.locale),
     }
+}
 
-    if !data.handlers.trim().is_empty() {
-        yaml.push_str("  handlers:\n");
-        for line in data.handlers.lines() {
-            yaml.push_str(&format!("    {}\n", line));
+// A WebSocket per tool: each text message updates the request and is answered with the
+// tool's result for it, numbered like the update. Opening the socket and every update take a
+// token from the tool's own rate limit, as calls to `/api/<tool>` do.
+fn open_live(req: Request, ctx: RouteContext<tools::Context>) -> Result<Response> {
+    let upgrade = req.headers().get("upgrade")?.unwrap_or_default();
+    if !upgrade.eq_ignore_ascii_case("websocket") {
+        let e = ToolError::UpgradeRequired(Message::new("error.upgrade_required", &[&req.path()]));
+        let mut resp = error_response(&e, ctx.data.locale)?;
+        resp.headers_mut().set("Upgrade", "websocket")?;
+        return Ok(resp);
+    }
+    let name = ctx.param("tool").cloned().unwrap_or_default();
+    let ip = client_ip(&req)?;
+    let env = ctx.env;
+    let data = ctx.data;
+    let mut session = match live::Session::open(&name, &data) {
+        Ok(session) => session,
+        Err(e) => return error_response(&e, data.locale),
+    };
+    let pair = WebSocketPair::new()?;
+    let server = pair.server;
+    server.accept()?;
+    wasm_bindgen_futures::spawn_local(async move {
+        let mut events = match server.events() {
+            Ok(events) => events,
+            Err(_) => return,
+        };
+        // Runs until the client closes the socket
+        while let Some(Ok(event)) = events.next().await {
+            let message = match event {
+                WebsocketEvent::Message(message) => message,
+                WebsocketEvent::Close(_) => break,
+            };
+            // Binary frames carry no update
+            let text = match message.text() {
+                Some(text) => text,
+                None => continue,
+            };
+            let reply = match live::Session::read(text, &data) {
+                Ok(update) => match charge(&env, &ip, &name).await {
+                    Ok(()) => session.handle(update, &data),
+                    Err(e) => live::reply(Some(update.seq), Err(e), &data),
+                },
+                Err(e) => live::reply(None, Err(e), &data),
+            };
+            if server.send(&reply).is_err() {
+                break;
+            }
         }
+    });
+    Response::from_websocket(pair.client)
+}
+
+fn not_found(path: &str) -> ToolError {
+    ToolError::NotFound(Message::new("error.not_found", &[&path]))
+}
+
+fn error_response(err: &ToolError, locale: Locale) -> Result<Response> {
+    let mut resp = Response::from_json(&err.envelope(locale))?.with_status(err.status());
+    if let ToolError::Unauthorized(_) = err {
+        resp.headers_mut().set("WWW-Authenticate", "Bearer")?;
     }
-    yaml
+    Ok(resp)
 }
@@ -0,0 +1,209 @@
+use crate::error::ToolError;
+use serde_json::Value;
+
+// Server-side caps on request size. Each one can be overridden by the wrangler var of the
+// same name; sizes are reported as 413, out-of-range field values as 422 naming the field.
+#[derive(Clone, Copy, Debug)]
+pub struct Limits {
+    // Raw body or query string, in bytes
+    pub max_body_bytes: usize,
+    // Any single string value in the input, in characters
+    pub max_string_length: usize,
+    // `count` of the generators (uuid, lorem, fake-user, credit-card); still parsed when
+    // they are left out of the build, so the same wrangler vars work for every build
+    #[cfg_attr(not(feature = "generators"), allow(dead_code))]
+    pub max_count: usize,
+    // `count` of the same generators on their `/stream` routes
+    #[cfg_attr(not(feature = "generators"), allow(dead_code))]
+    pub max_stream_count: usize,
+    // `length` of generated secrets (password, token)
+    #[cfg_attr(not(feature = "crypto"), allow(dead_code))]
+    pub max_length: usize,
+    pub max_batch_items: usize,
+    pub max_pipeline_steps: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_body_bytes: 1024 * 1024,
+            max_string_length: 256 * 1024,
+            max_count: 1000,
+            max_stream_count: 100_000,
+            max_length: 4096,
+            max_batch_items: 100,
+            max_pipeline_steps: 20,
+        }
+    }
+}
+
+impl Limits {
+    // `var` looks up a configuration value; unset or unparsable values keep the default.
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Self {
+        let get = |name: &str, default: usize| {
+            var(name)
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or(default)
+        };
+        let d = Limits::default();
+        Limits {
+            max_body_bytes: get("MAX_BODY_BYTES", d.max_body_bytes),
+            max_string_length: get("MAX_STRING_LENGTH", d.max_string_length),
+            max_count: get("MAX_COUNT", d.max_count),
+            max_stream_count: get("MAX_STREAM_COUNT", d.max_stream_count),
+            max_length: get("MAX_LENGTH", d.max_length),
+            max_batch_items: get("MAX_BATCH_ITEMS", d.max_batch_items),
+            max_pipeline_steps: get("MAX_PIPELINE_STEPS", d.max_pipeline_steps),
+        }
+    }
+
+    pub fn body(&self, bytes: usize) -> Result<(), ToolError> {
+        exceeded("MAX_BODY_BYTES", self.max_body_bytes, bytes)
+    }
+
+    pub fn batch_items(&self, items: usize) -> Result<(), ToolError> {
+        exceeded("MAX_BATCH_ITEMS", self.max_batch_items, items)
+    }
+
+    pub fn pipeline_steps(&self, steps: usize) -> Result<(), ToolError> {
+        exceeded("MAX_PIPELINE_STEPS", self.max_pipeline_steps, steps)
+    }
+
+    pub fn string(&self, s: &str) -> Result<(), ToolError> {
+        // Byte length bounds the char count, so only count chars when it could matter
+        if s.len() <= self.max_string_length {
+            return Ok(());
+        }
+        exceeded(
+            "MAX_STRING_LENGTH",
+            self.max_string_length,
+            s.chars().count(),
+        )
+    }
+
+    // Every string in a parsed input, including object keys.
+    pub fn strings(&self, value: &Value) -> Result<(), ToolError> {
+        match value {
+            Value::String(s) => self.string(s),
+            Value::Array(items) => items.iter().try_for_each(|v| self.strings(v)),
+            Value::Object(map) => map.iter().try_for_each(|(k, v)| {
+                self.string(k)?;
+                self.strings(v)
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    #[cfg(feature = "generators")]
+    pub fn count(&self, field: &'static str, count: usize) -> Result<(), ToolError> {
+        in_range(field, "MAX_COUNT", self.max_count, count)
+    }
+
+    #[cfg(feature = "generators")]
+    pub fn stream_count(&self, field: &'static str, count: usize) -> Result<(), ToolError> {
+        in_range(field, "MAX_STREAM_COUNT", self.max_stream_count, count)
+    }
+
+    #[cfg(feature = "crypto")]
+    pub fn length(&self, field: &'static str, length: usize) -> Result<(), ToolError> {
+        in_range(field, "MAX_LENGTH", self.max_length, length)
+    }
+}
+
+fn exceeded(limit: &'static str, max: usize, actual: usize) -> Result<(), ToolError> {
+    if actual > max {
+        return Err(ToolError::LimitExceeded { limit, max, actual });
+    }
+    Ok(())
+}
+
+#[cfg(any(feature = "crypto", feature = "generators"))]
+fn in_range(field: &'static str, limit: &str, max: usize, actual: usize) -> Result<(), ToolError> {
+    if actual > max {
+        return Err(ToolError::invalid(
+            field,
+            "error.at_most",
+            &[&field, &max, &limit, &actual],
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits() -> Limits {
+        Limits::from_vars(|name| match name {
+            "MAX_BODY_BYTES" => Some("10".to_string()),
+            "MAX_STRING_LENGTH" => Some(" 3 ".to_string()),
+            "MAX_BATCH_ITEMS" => Some("2".to_string()),
+            "MAX_PIPELINE_STEPS" => Some("two".to_string()),
+            "MAX_COUNT" | "MAX_STREAM_COUNT" | "MAX_LENGTH" => Some("5".to_string()),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn vars_override_and_bad_values_keep_the_default() {
+        let limits = limits();
+        assert_eq!(limits.max_body_bytes, 10);
+        assert_eq!(limits.max_string_length, 3);
+        assert_eq!(
+            limits.max_pipeline_steps,
+            Limits::default().max_pipeline_steps
+        );
+    }
+
+    #[test]
+    fn sizes_at_the_limit_pass_and_one_more_is_413() {
+        let limits = limits();
+        for (check, max) in [
+            (
+                Limits::body as fn(&Limits, usize) -> Result<(), ToolError>,
+                10,
+            ),
+            (Limits::batch_items, 2),
+            (Limits::pipeline_steps, 20),
+        ] {
+            assert!(check(&limits, max).is_ok());
+            let err = check(&limits, max + 1).unwrap_err();
+            assert_eq!(err.status(), 413);
+        }
+    }
+
+    #[test]
+    fn strings_are_measured_in_characters_including_keys() {
+        let limits = limits();
+        assert!(limits.string("abc").is_ok());
+        assert!(limits.string("汉字符").is_ok());
+        assert_eq!(limits.string("abcd").unwrap_err().status(), 413);
+        assert!(limits
+            .strings(&serde_json::json!({ "key": ["abc", 12345, { "k": "汉字符" }] }))
+            .is_ok());
+        assert!(limits.strings(&serde_json::json!([["abcd"]])).is_err());
+        assert!(limits.strings(&serde_json::json!({ "long": 1 })).is_err());
+    }
+
+    #[cfg(feature = "generators")]
+    #[test]
+    fn counts_at_the_limit_pass_and_one_more_is_422() {
+        let limits = limits();
+        assert!(limits.count("count", 5).is_ok());
+        assert!(limits.stream_count("count", 5).is_ok());
+        for err in [
+            limits.count("count", 6).unwrap_err(),
+            limits.stream_count("count", 6).unwrap_err(),
+        ] {
+            assert_eq!(err.status(), 422);
+        }
+    }
+
+    #[cfg(feature = "crypto")]
+    #[test]
+    fn lengths_at_the_limit_pass_and_one_more_is_422() {
+        let limits = limits();
+        assert!(limits.length("length", 5).is_ok());
+        assert_eq!(limits.length("length", 6).unwrap_err().status(), 422);
+    }
+}
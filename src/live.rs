@@ -0,0 +1,200 @@
+use crate::error::{ErrorBody, ToolError};
+use crate::i18n::Message;
+use crate::tools::{Context, DynTool, Input};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+// Tools worth re-running on every keystroke: cheap, pure and text in, text out.
+pub const TOOLS: [&str; 4] = ["regex", "diff", "sql", "json"];
+
+// One client message. `set` replaces whole fields, `edits` splice text into string fields,
+// so a keystroke in a large document does not resend it.
+#[derive(Deserialize)]
+pub struct Update {
+    pub seq: u64,
+    #[serde(default)]
+    set: Map<String, Value>,
+    #[serde(default)]
+    edits: Vec<Edit>,
+}
+
+// Replaces `start..end` of `field` with `text`. Offsets count UTF-16 code units, as
+// `selectionStart` and `selectionEnd` of a textarea do.
+#[derive(Deserialize)]
+struct Edit {
+    field: String,
+    start: usize,
+    end: usize,
+    #[serde(default)]
+    text: String,
+}
+
+// The answer to the update numbered `seq`; `None` when the message could not be read.
+// Clients drop replies older than the last update they sent.
+#[derive(Serialize)]
+pub struct Reply {
+    pub seq: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorBody>,
+}
+
+// A tool and the request built up from the updates received so far.
+pub struct Session {
+    tool: &'static dyn DynTool,
+    request: Map<String, Value>,
+    seq: Option<u64>,
+}
+
+impl Session {
+    pub fn open(name: &str, ctx: &Context) -> Result<Session, ToolError> {
+        let tool = ctx
+            .tools
+            .find(name)
+            .ok_or_else(|| ToolError::unknown_tool(name))?;
+        if !TOOLS.contains(&name) {
+            return Err(ToolError::NotFound(Message::new(
+                "live.unsupported",
+                &[&name, &TOOLS.join(", ")],
+            )));
+        }
+        ctx.access.check(tool.group())?;
+        Ok(Session {
+            tool,
+            request: Map::new(),
+            seq: None,
+        })
+    }
+
+    // Parses one message under the body limits. One that cannot be read is answered with
+    // `reply(None, ..)`, as its `seq` is unknown.
+    pub fn read(message: String, ctx: &Context) -> Result<Update, ToolError> {
+        Input::Body(message).parse(&ctx.limits)
+    }
+
+    // Updates must be numbered in increasing order; one that is not is answered with an
+    // error and changes nothing. A failing edit, or one that takes the request over the
+    // limits, leaves the request as it was.
+    pub fn handle(&mut self, update: Update, ctx: &Context) -> Reply {
+        let seq = update.seq;
+        if let Some(last) = self.seq.filter(|last| seq <= *last) {
+            let e = ToolError::invalid("seq", "live.stale", &[&seq, &last]);
+            return reply(Some(seq), Err(e), ctx);
+        }
+        self.seq = Some(seq);
+        let result = self.apply(update, ctx).and_then(|()| {
+            self.tool
+                .call(Input::Json(Value::Object(self.request.clone())), ctx)
+        });
+        reply(Some(seq), result, ctx)
+    }
+
+    fn apply(&mut self, update: Update, ctx: &Context) -> Result<(), ToolError> {
+        let mut request = self.request.clone();
+        request.extend(update.set);
+        for edit in update.edits {
+            let mut text: Vec<u16> = match request.get(&edit.field) {
+                Some(Value::String(s)) => s.encode_utf16().collect(),
+                None => Vec::new(),
+                Some(_) => return Err(bad_edit(&edit)),
+            };
+            if edit.start > edit.end || edit.end > text.len() {
+                return Err(bad_edit(&edit));
+            }
+            text.splice(edit.start..edit.end, edit.text.encode_utf16());
+            // Splitting a surrogate pair leaves text that is not UTF-16
+            let text = String::from_utf16(&text).map_err(|_| bad_edit(&edit))?;
+            request.insert(edit.field, Value::String(text));
+        }
+        // Edits grow the request without resending it, so the limits on a body apply to the
+        // request they add up to
+        let merged = Value::Object(request);
+        ctx.limits.strings(&merged)?;
+        ctx.limits.body(merged.to_string().len())?;
+        if let Value::Object(request) = merged {
+            self.request = request;
+        }
+        Ok(())
+    }
+}
+
+pub fn reply(seq: Option<u64>, result: Result<Value, ToolError>, ctx: &Context) -> Reply {
+    match result {
+        Ok(result) => Reply {
+            seq,
+            result: Some(result),
+            error: None,
+        },
+        Err(e) => Reply {
+            seq,
+            result: None,
+            error: Some(e.envelope(ctx.locale).error),
+        },
+    }
+}
+
+fn bad_edit(edit: &Edit) -> ToolError {
+    ToolError::invalid(
+        "edits",
+        "live.bad_edit",
+        &[&edit.start, &edit.end, &edit.field],
+    )
+}
+
+// Every live tool belongs to one of these groups
+#[cfg(all(test, any(feature = "text", feature = "formats")))]
+mod tests {
+    use super::*;
+
+    fn send(session: &mut Session, message: &str, ctx: &Context) -> Reply {
+        match Session::read(message.to_string(), ctx) {
+            Ok(update) => session.handle(update, ctx),
+            Err(e) => reply(None, Err(e), ctx),
+        }
+    }
+
+    #[cfg(feature = "text")]
+    #[test]
+    fn updates_over_the_limits_change_nothing() {
+        let mut ctx = Context::default();
+        ctx.limits.max_body_bytes = 100;
+        let mut session = Session::open("diff", &ctx).unwrap();
+        let reply = send(
+            &mut session,
+            r#"{"seq": 1, "set": {"old": "a", "new": "b"}}"#,
+            &ctx,
+        );
+        assert!(reply.error.is_none());
+
+        // Each edit fits in a message, but the request they add up to outgrows the limit
+        let append = |seq: u64| {
+            format!(
+                r#"{{"seq": {}, "edits": [{{"field": "old", "start": 0, "end": 0, "text": "{}"}}]}}"#,
+                seq,
+                "x".repeat(20)
+            )
+        };
+        for seq in 2..5 {
+            assert!(send(&mut session, &append(seq), &ctx).error.is_none());
+        }
+        let reply = send(&mut session, &append(5), &ctx);
+        assert_eq!(reply.seq, Some(5));
+        assert!(reply.error.is_some());
+        assert_eq!(session.request["old"].as_str().unwrap().len(), 61);
+
+        let reply = send(&mut session, r#"{"seq": 6, "set": {"new": "c"}}"#, &ctx);
+        assert!(reply.error.is_none());
+        assert_eq!(session.request["new"], "c");
+    }
+
+    #[cfg(feature = "formats")]
+    #[test]
+    fn unreadable_messages_have_no_seq() {
+        let ctx = Context::default();
+        let mut session = Session::open("json", &ctx).unwrap();
+        let reply = send(&mut session, "not json", &ctx);
+        assert_eq!(reply.seq, None);
+        assert!(reply.error.is_some());
+    }
+}
@@ -0,0 +1,262 @@
+use crate::error::ToolError;
+use crate::i18n::tr;
+use crate::tools::{Context, Input};
+use schemars::generate::SchemaSettings;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::future::Future;
+
+// Model Context Protocol revisions this server speaks, newest first.
+const PROTOCOL_VERSIONS: [&str; 3] = ["2025-06-18", "2025-03-26", "2024-11-05"];
+
+// JSON-RPC 2.0 error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+#[derive(Deserialize)]
+struct Request {
+    jsonrpc: String,
+    // Absent for notifications, which get no reply
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Deserialize)]
+struct CallParams {
+    name: String,
+    #[serde(default)]
+    arguments: Map<String, Value>,
+}
+
+type RpcError = (i64, String);
+
+// Answers a JSON-RPC message or batch of messages. `None` when nothing needs a reply, i.e.
+// the body held only notifications. An oversized body is the usual 413 rather than a
+// JSON-RPC error, as Content-Length may be absent. `charge` takes a token from a tool's rate
+// limit before each `tools/call`, as a call to `/api/<tool>` would.
+pub async fn handle<F, Fut>(
+    body: &str,
+    ctx: &Context,
+    mut charge: F,
+) -> Result<Option<Value>, ToolError>
+where
+    F: FnMut(&'static str) -> Fut,
+    Fut: Future<Output = Result<(), ToolError>>,
+{
+    ctx.limits.body(body.len())?;
+    let message: Value = match serde_json::from_str(body) {
+        Ok(message) => message,
+        Err(e) => return Ok(Some(error(Value::Null, (PARSE_ERROR, e.to_string())))),
+    };
+    Ok(match message {
+        Value::Array(batch) => {
+            if batch.is_empty() {
+                return Ok(Some(error(Value::Null, invalid_request(ctx))));
+            }
+            if let Err(e) = ctx.limits.batch_items(batch.len()) {
+                let e = (INVALID_REQUEST, e.message(ctx.locale));
+                return Ok(Some(error(Value::Null, e)));
+            }
+            let mut replies = Vec::new();
+            for message in batch {
+                replies.extend(reply(message, ctx, &mut charge).await);
+            }
+            if replies.is_empty() {
+                None
+            } else {
+                Some(Value::Array(replies))
+            }
+        }
+        message => reply(message, ctx, &mut charge).await,
+    })
+}
+
+async fn reply<F, Fut>(message: Value, ctx: &Context, charge: &mut F) -> Option<Value>
+where
+    F: FnMut(&'static str) -> Fut,
+    Fut: Future<Output = Result<(), ToolError>>,
+{
+    let request = match serde_json::from_value::<Request>(message) {
+        Ok(request) if request.jsonrpc == "2.0" => request,
+        _ => return Some(error(Value::Null, invalid_request(ctx))),
+    };
+    // Notifications (`notifications/initialized`, `notifications/cancelled`) need no action
+    let id = request.id?;
+    let result = match request.method.as_str() {
+        "initialize" => Ok(initialize(&request.params)),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(list(ctx)),
+        "tools/call" => call(request.params, ctx, charge).await,
+        method => Err((
+            METHOD_NOT_FOUND,
+            tr(ctx.locale, "mcp.method_not_found", &[&method]),
+        )),
+    };
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(e) => error(id, e),
+    })
+}
+
+// Agrees on the client's protocol revision when it is one we know, otherwise offers ours.
+fn initialize(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let version = PROTOCOL_VERSIONS
+        .iter()
+        .copied()
+        .find(|v| Some(*v) == requested)
+        .unwrap_or(PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": { "listChanged": false } },
+        "serverInfo": {
+            "name": env!("CARGO_PKG_NAME"),
+            "version": env!("CARGO_PKG_VERSION")
+        }
+    })
+}
+
+// The tools this deployment serves and the caller's API key may call. Schemas are inlined,
+// since each one has to stand on its own.
+fn list(ctx: &Context) -> Value {
+    let mut settings = SchemaSettings::draft2020_12();
+    settings.inline_subschemas = true;
+    settings.meta_schema = None;
+    let mut gen = settings.into_generator();
+    let tools: Vec<Value> = ctx
+        .tools
+        .tools()
+        .filter(|tool| ctx.access.allows(tool.group()))
+        .map(|tool| {
+            json!({
+                "name": tool.name(),
+                "description": tool.description(ctx.locale),
+                "inputSchema": tool.request_schema(&mut gen),
+                "outputSchema": tool.response_schema(&mut gen)
+            })
+        })
+        .collect();
+    json!({ "tools": tools })
+}
+
+// Failures of the tool itself, including a used-up rate limit, are results with `isError`,
+// so the model can read the message and retry; only an unknown tool or malformed params are
+// protocol errors.
+async fn call<F, Fut>(params: Value, ctx: &Context, charge: &mut F) -> Result<Value, RpcError>
+where
+    F: FnMut(&'static str) -> Fut,
+    Fut: Future<Output = Result<(), ToolError>>,
+{
+    let params: CallParams =
+        serde_json::from_value(params).map_err(|e| (INVALID_PARAMS, e.to_string()))?;
+    let tool = ctx.tools.find(&params.name).ok_or_else(|| {
+        (
+            INVALID_PARAMS,
+            ToolError::unknown_tool(&params.name).message(ctx.locale),
+        )
+    })?;
+    let outcome = match charge(tool.name()).await {
+        Ok(()) => tool.call(Input::Json(Value::Object(params.arguments)), ctx),
+        Err(e) => Err(e),
+    };
+    Ok(match outcome {
+        Ok(output) => json!({
+            "content": [{ "type": "text", "text": output.to_string() }],
+            "structuredContent": output,
+            "isError": false
+        }),
+        Err(e) => json!({
+            "content": [{ "type": "text", "text": e.message(ctx.locale) }],
+            "isError": true
+        }),
+    })
+}
+
+fn invalid_request(ctx: &Context) -> RpcError {
+    (INVALID_REQUEST, tr(ctx.locale, "mcp.invalid_request", &[]))
+}
+
+fn error(id: Value, (code, message): RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ratelimit::testing::{block_on, charge};
+
+    fn handle_in(body: &str, ctx: &Context) -> Result<Option<Value>, ToolError> {
+        block_on(super::handle(body, ctx, charge(1)))
+    }
+
+    fn handle(body: &str) -> Value {
+        handle_in(body, &Context::default()).unwrap().unwrap()
+    }
+
+    #[test]
+    fn unparsable_bodies_are_parse_errors() {
+        let reply = handle("{\"jsonrpc\": \"2.0\",");
+        assert_eq!(reply["error"]["code"], PARSE_ERROR);
+        assert_eq!(reply["id"], Value::Null);
+    }
+
+    #[test]
+    fn unknown_methods_and_bad_requests_are_errors() {
+        let reply = handle(r#"{"jsonrpc": "2.0", "id": 7, "method": "tools/delete"}"#);
+        assert_eq!(reply["id"], 7);
+        assert_eq!(reply["error"]["code"], METHOD_NOT_FOUND);
+        assert!(reply["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("tools/delete"));
+
+        let reply = handle(r#"{"jsonrpc": "1.0", "id": 1, "method": "ping"}"#);
+        assert_eq!(reply["error"]["code"], INVALID_REQUEST);
+        assert_eq!(handle("[]")["error"]["code"], INVALID_REQUEST);
+    }
+
+    #[test]
+    fn notifications_get_no_reply() {
+        let body = r#"[{"jsonrpc": "2.0", "method": "notifications/initialized"}]"#;
+        assert_eq!(handle_in(body, &Context::default()).unwrap(), None);
+        let reply = handle(
+            r#"[{"jsonrpc": "2.0", "method": "notifications/initialized"},
+                {"jsonrpc": "2.0", "id": 1, "method": "ping"}]"#,
+        );
+        assert_eq!(reply.as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn oversized_bodies_are_413() {
+        let mut ctx = Context::default();
+        let body = r#"{"jsonrpc": "2.0", "id": 1, "method": "ping"}"#;
+        ctx.limits.max_body_bytes = body.len();
+        assert!(handle_in(body, &ctx).is_ok());
+        ctx.limits.max_body_bytes = body.len() - 1;
+        assert_eq!(handle_in(body, &ctx).unwrap_err().status(), 413);
+    }
+
+    #[cfg(feature = "crypto")]
+    #[test]
+    fn calls_over_the_tool_budget_are_tool_errors() {
+        let call = |id: u32| {
+            json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "method": "tools/call",
+                "params": { "name": "md5", "arguments": { "text": "abc" } }
+            })
+        };
+        let replies = handle(&json!([call(1), call(2)]).to_string());
+        assert_eq!(replies[0]["result"]["isError"], false);
+        assert_eq!(replies[1]["result"]["isError"], true);
+    }
+}
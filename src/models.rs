@@ -1,45 +1,74 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 // --- Request Structs (from lib.rs) ---
 
-#[derive(Deserialize)]
+// The field a tool works on is required and unknown fields are rejected, so a missing or
+// misspelled field is reported instead of running on empty input. Options default.
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct SqlRequest {
     pub sql: String,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct DiffRequest {
     pub old: String,
     pub new: String,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct CronRequest {
     pub cron: String,
+    // Unix seconds to list the runs after instead of now; fixes the response so it can be cached
+    pub from: Option<i64>,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct SubnetRequest {
     pub ip: String,
+    #[serde(default = "default_cidr")]
     pub cidr: u8,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct RegexGenRequest {
+    #[schemars(extend("enum" = ["email", "phone_cn", "ipv4", "url"]))]
     pub key: String,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct RegexRequest {
     pub pattern: String,
     pub text: String,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
 pub struct UuidRequest {
     pub count: usize,
     pub hyphens: bool,
     pub uppercase: bool,
-}
-#[derive(Deserialize)]
+    // Same seed, same output; echoed back when given
+    pub seed: Option<u64>,
+}
+impl Default for UuidRequest {
+    fn default() -> Self {
+        UuidRequest {
+            count: 1,
+            hyphens: true,
+            uppercase: false,
+            seed: None,
+        }
+    }
+}
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct JwtRequest {
     pub token: String,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
 pub struct PasswordRequest {
     pub length: usize,
     pub uppercase: bool,
@@ -47,71 +76,116 @@ pub struct PasswordRequest {
     pub numbers: bool,
     pub symbols: bool,
 }
-#[derive(Deserialize)]
+impl Default for PasswordRequest {
+    fn default() -> Self {
+        PasswordRequest {
+            length: 16,
+            uppercase: true,
+            lowercase: true,
+            numbers: true,
+            symbols: true,
+        }
+    }
+}
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
 pub struct TokenRequest {
     pub length: usize,
     pub uppercase: bool,
     pub lowercase: bool,
     pub numbers: bool,
     pub symbols: bool,
-}
-#[derive(Deserialize)]
+    // Makes the token reproducible, and so no longer secret; echoed when given
+    pub seed: Option<u64>,
+}
+impl Default for TokenRequest {
+    fn default() -> Self {
+        TokenRequest {
+            length: 32,
+            uppercase: true,
+            lowercase: true,
+            numbers: true,
+            symbols: false,
+            seed: None,
+        }
+    }
+}
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Base64Request {
     pub text: String,
+    #[schemars(extend("enum" = ["encode", "decode"]))]
+    #[serde(default = "default_base64_action")]
     pub action: String,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct JsonRequest {
     pub input: String,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct EscapeRequest {
     pub text: String,
+    #[schemars(extend("enum" = ["html_enc", "html_dec", "json_enc", "json_dec"]))]
     pub mode: String,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct DateRequest {
     pub input: String,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct ColorRequest {
     pub input: String,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct QrRequest {
     pub text: String,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct JsEncRequest {
     pub js: String,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct YamlRequest {
     pub yaml: String,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct TomlRequest {
     pub toml: String,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Md5Request {
     pub text: String,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct ChmodRequest {
     pub octal: String,
+    #[serde(default)]
     pub file: String,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct UrlRequest {
     pub input: String,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct CaseRequest {
     pub text: String,
+    #[schemars(extend("enum" = ["camel", "pascal", "snake", "kebab", "constant", "upper", "lower"]))]
     pub mode: String,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Default, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
 pub struct TarRequest {
     pub op: String,
     pub comp: String,
@@ -119,7 +193,8 @@ pub struct TarRequest {
     pub archive: String,
     pub files: String,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Default, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
 pub struct PsRequest {
     pub format: String,
     pub sort: String,
@@ -130,7 +205,8 @@ pub struct PsRequest {
     pub user: String,
     pub pid: String,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Default, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
 pub struct TcpdumpRequest {
     pub interface: String,
     pub protocol: String,
@@ -142,7 +218,8 @@ pub struct TcpdumpRequest {
     pub write_file: String,
     pub count: String,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Default, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
 pub struct GitRequest {
     pub cmd: String,
     pub target: String,
@@ -159,14 +236,16 @@ pub struct GitRequest {
     pub opt_oneline: bool,
     pub opt_graph: bool,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Default, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
 pub struct GitCmdRequest {
     pub action: String,
     pub tag: String,
     pub msg: String,
     pub branch: String,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Default, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
 pub struct StraceRequest {
     pub target: String,
     pub is_pid: bool,
@@ -177,7 +256,8 @@ pub struct StraceRequest {
     pub string_limit: String,
     pub timestamp: bool,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Default, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
 pub struct IostatRequest {
     pub interval: String,
     pub count: String,
@@ -188,7 +268,8 @@ pub struct IostatRequest {
     pub timestamp: bool,
     pub device: String,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Default, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
 pub struct NiceRequest {
     pub mode: String,
     pub priority: i32,
@@ -196,7 +277,8 @@ pub struct NiceRequest {
     pub target_type: String,
     pub target: String,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Default, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
 pub struct LsRequest {
     pub path: String,
     pub all: bool,
@@ -209,7 +291,8 @@ pub struct LsRequest {
     pub directory: bool,
     pub color: bool,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Default, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
 pub struct FirewallRequest {
     pub op: String,
     pub zone: String,
@@ -217,7 +300,8 @@ pub struct FirewallRequest {
     pub target: String,
     pub permanent: bool,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Default, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
 pub struct SystemctlRequest {
     pub operation: String,
     pub service: String,
@@ -226,7 +310,8 @@ pub struct SystemctlRequest {
     pub force: bool,
     pub global: bool,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Default, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
 pub struct FindRequest {
     pub path: String,
     pub name: String,
@@ -237,11 +322,13 @@ pub struct FindRequest {
     pub empty: bool,
     pub exec: String,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Default, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
 pub struct DockerfileRequest {
     pub stages: Vec<DockerfileStage>,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
 pub struct NginxRequest {
     pub domain: String,
     pub port: u16,
@@ -259,12 +346,47 @@ pub struct NginxRequest {
     pub proxy_read_timeout: String,
     pub proxy_send_timeout: String,
 }
-#[derive(Deserialize)]
+impl Default for NginxRequest {
+    fn default() -> Self {
+        NginxRequest {
+            domain: String::new(),
+            port: 80,
+            root: String::new(),
+            locations: Vec::new(),
+            upstream: String::new(),
+            https: false,
+            force_https: false,
+            ssl_cert: String::new(),
+            ssl_key: String::new(),
+            gzip: false,
+            client_max_body_size: String::new(),
+            keepalive_timeout: String::new(),
+            proxy_connect_timeout: String::new(),
+            proxy_read_timeout: String::new(),
+            proxy_send_timeout: String::new(),
+        }
+    }
+}
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
 pub struct LoremRequest {
     pub count: usize,
+    #[schemars(extend("enum" = ["words", "sentences", "paragraphs"]))]
     pub mode: String,
-}
-#[derive(Deserialize)]
+    // Same seed, same output; drawn at random and echoed back when omitted
+    pub seed: Option<u64>,
+}
+impl Default for LoremRequest {
+    fn default() -> Self {
+        LoremRequest {
+            count: 1,
+            mode: "paragraphs".to_string(),
+            seed: None,
+        }
+    }
+}
+#[derive(Deserialize, Serialize, Default, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
 pub struct RsyncRequest {
     pub source: String,
     pub user: String,
@@ -280,39 +402,73 @@ pub struct RsyncRequest {
     pub ssh: bool,
     pub exclude: String,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
 pub struct FakeUserRequest {
     pub count: usize,
+    #[schemars(extend("enum" = ["en", "cn"]))]
     pub locale: String,
-}
-#[derive(Deserialize)]
+    // Same seed, same output; drawn at random and echoed back when omitted
+    pub seed: Option<u64>,
+}
+impl Default for FakeUserRequest {
+    fn default() -> Self {
+        FakeUserRequest {
+            count: 1,
+            locale: "en".to_string(),
+            seed: None,
+        }
+    }
+}
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct UnitRequest {
     pub value: String, // Frontend sends as string
     #[serde(rename = "type")]
+    #[schemars(extend("enum" = ["storage", "time"]))]
     pub type_: String,
     pub from: String,
     pub to: String,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct CurlRequest {
+    #[serde(default)]
     pub method: String,
     pub url: String,
+    #[serde(default)]
     pub headers: String,
+    #[serde(default)]
     pub body: String,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
 pub struct CreditCardRequest {
     pub count: usize,
+    #[schemars(extend("enum" = ["visa", "mastercard", "amex", "discover"]))]
     pub issuer: String,
-}
-#[derive(Deserialize)]
+    // Same seed, same output; drawn at random and echoed back when omitted
+    pub seed: Option<u64>,
+}
+impl Default for CreditCardRequest {
+    fn default() -> Self {
+        CreditCardRequest {
+            count: 1,
+            issuer: "visa".to_string(),
+            seed: None,
+        }
+    }
+}
+#[derive(Deserialize, Serialize, Default, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
 pub struct AwkRequest {
     pub separator: String,
     pub variable: String,
     pub code: String,
     pub file: String,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Default, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
 pub struct SedRequest {
     pub operation: String,
     pub pattern: String,
@@ -321,7 +477,8 @@ pub struct SedRequest {
     pub inplace: bool,
     pub file: String,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Default, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
 pub struct RegexBuildRequest {
     pub starts_with: String,
     pub not_starts_with: String,
@@ -333,23 +490,27 @@ pub struct RegexBuildRequest {
 
 // --- Response Structs (from lib.rs) ---
 
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct GenericResponse {
     pub result: String,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct UuidResponse {
     pub uuids: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct TokenResponse {
     pub token: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct PasswordResponse {
     pub password: String,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct UrlResponse {
     pub encoded: String,
     pub decoded: String,
@@ -358,18 +519,12 @@ pub struct UrlResponse {
     pub path: String,
     pub params: Vec<(String, String)>,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct JsonResponse {
     pub pretty: String,
     pub minified: String,
-    pub error: Option<String>,
 }
-#[derive(Serialize)]
-pub struct YamlResponse {
-    pub result: String,
-    pub error: Option<String>,
-}
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct WhoamiResponse {
     pub ip: String,
     pub country: String,
@@ -378,44 +533,50 @@ pub struct WhoamiResponse {
     pub user_agent: String,
     pub headers: std::collections::HashMap<String, String>,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct FakeUserResponse {
     pub users: Vec<FakeUser>,
+    // The request's seed, or the one drawn for it
+    pub seed: u64,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct CreditCardResponse {
     pub cards: Vec<CreditCard>,
+    // The request's seed, or the one drawn for it
+    pub seed: u64,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
+pub struct LoremResponse {
+    pub result: String,
+    // The request's seed, or the one drawn for it
+    pub seed: u64,
+}
+#[derive(Serialize, JsonSchema)]
 pub struct QrResponse {
     pub svg: String,
-    pub error: Option<String>,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct RegexPatternResponse {
     pub pattern: String,
 }
 
 // --- Structs from utils.rs ---
 
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct DiffChunk {
     pub tag: String,
     pub text: String,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct DiffResponse {
     pub chunks: Vec<DiffChunk>,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct CronResponse {
-    pub valid: bool,
     pub next_runs: Vec<String>,
-    pub error: String,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct SubnetResponse {
-    pub valid: bool,
     pub ip: String,
     pub cidr: String,
     pub mask: String,
@@ -431,107 +592,101 @@ pub struct SubnetResponse {
     pub binary_ip: String,
     pub binary_mask: String,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct RegexResponse {
     pub matches: Vec<String>,
     pub count: usize,
-    pub error: Option<String>,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct RegexBuildResponse {
     pub pattern: String,
-    pub error: Option<String>,
 }
 pub struct UuidConfig {
     pub count: usize,
     pub hyphens: bool,
     pub uppercase: bool,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct Md5Response {
     pub md5_32_lower: String,
     pub md5_32_upper: String,
     pub md5_16_lower: String,
     pub md5_16_upper: String,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct DateResponse {
-    pub valid: bool,
     pub unix_sec: i64,
     pub unix_milli: i64,
     pub iso_8601: String,
     pub human_utc: String,
-    pub error: Option<String>,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct ColorResponse {
-    pub valid: bool,
     pub hex: String,
     pub rgb: String,
     pub hsl: String,
     pub cmyk: String,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct ChmodResponse {
-    pub valid: bool,
     pub command: String,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct JwtResponse {
-    pub error: Option<String>,
     pub header: String,
     pub payload: String,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct TarResponse {
     pub command: String,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct PsResponse {
     pub command: String,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct TcpdumpResponse {
     pub command: String,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct GitResponse {
     pub command: String,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct GitCmdResponse {
     pub command: String,
     pub description: String,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct StraceResponse {
     pub command: String,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct IostatResponse {
     pub command: String,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct NiceResponse {
     pub command: String,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct LsResponse {
     pub command: String,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct FirewallResponse {
     pub command: String,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct SystemctlResponse {
     pub command: String,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct FindResponse {
     pub command: String,
 }
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct DockerfileStage {
     #[serde(default)]
     pub image: String,
@@ -562,14 +717,14 @@ pub struct DockerfileStage {
     #[serde(default)]
     pub healthcheck: String,
 }
-#[derive(Deserialize, Serialize, Clone)]
+#[derive(Deserialize, Serialize, Clone, JsonSchema)]
 pub struct NginxLocation {
     pub path: String,
     pub proxy: String,
     pub root: String,
     pub spa: bool,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct UnitResponse {
     pub result: f64,
     pub value: f64,
@@ -578,35 +733,249 @@ pub struct UnitResponse {
     #[serde(rename = "type")]
     pub type_: String,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct CurlResponse {
     pub command: String,
     pub python: String,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct RsyncResponse {
     pub command: String,
     pub ssh_config: String,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct FakeUser {
     pub name: String,
     pub email: String,
     pub address: String,
     pub phone: String,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct CreditCard {
     pub number: String,
     pub issuer: String,
     pub expiry: String,
     pub cvv: String,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct AwkResponse {
     pub command: String,
 }
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct SedResponse {
     pub command: String,
 }
+
+// --- DevOps Generator Structs (from lib.rs) ---
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct K8sRequest {
+    #[serde(default = "default_kind")]
+    #[schemars(extend("enum" = ["Deployment", "Service", "Ingress", "CronJob", "ConfigMap", "Secret"]))]
+    pub kind: String,
+    #[serde(default = "default_name")]
+    pub name: String,
+    #[serde(default = "default_namespace")]
+    pub namespace: String,
+    #[serde(default = "default_image")]
+    pub image: String,
+    #[serde(default = "default_replicas")]
+    pub replicas: i32,
+    #[serde(default = "default_port")]
+    pub port: i32,
+    #[serde(default = "default_target_port")]
+    pub target_port: i32,
+    #[serde(default = "default_service_type")]
+    pub service_type: String,
+    #[serde(default = "default_ingress_host")]
+    pub ingress_host: String,
+    #[serde(default = "default_ingress_path")]
+    pub ingress_path: String,
+    #[serde(default = "default_pull_policy")]
+    pub pull_policy: String,
+    pub cpu_limit: Option<String>,
+    pub memory_limit: Option<String>,
+    pub cpu_request: Option<String>,
+    pub memory_request: Option<String>,
+    #[serde(default)]
+    pub env: Vec<K8sEnvVar>,
+    #[serde(default = "default_schedule")]
+    pub schedule: String,
+    #[serde(default = "default_restart_policy")]
+    pub restart_policy: String,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct K8sCmdRequest {
+    #[schemars(extend("enum" = ["get", "describe", "delete", "logs", "exec", "scale", "port_forward", "rollout_restart", "rollout_status", "rollout_history", "rollout_undo"]))]
+    pub action: String,
+    #[serde(default = "default_namespace")]
+    pub namespace: String,
+    #[serde(default = "default_resource_type")]
+    pub resource_type: String,
+    #[serde(default)]
+    pub resource_name: String,
+    #[serde(default)]
+    pub replicas: i32,
+    #[serde(default)]
+    pub local_port: i32,
+    #[serde(default)]
+    pub remote_port: i32,
+    #[serde(default)]
+    pub output_format: String,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ComposeRequest {
+    #[serde(default = "default_name")]
+    pub name: String,
+    #[serde(default = "default_image")]
+    pub image: String,
+    #[serde(default)]
+    pub build: bool,
+    // Container port; 0 publishes nothing
+    #[serde(default = "default_port")]
+    pub port: i32,
+    // Published port on the host; 0 means the same as `port`
+    #[serde(default)]
+    pub host_port: i32,
+    #[serde(default)]
+    pub env: Vec<K8sEnvVar>,
+    #[serde(default = "default_compose_restart")]
+    #[schemars(extend("enum" = ["no", "always", "on-failure", "unless-stopped"]))]
+    pub restart: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct K8sCmdResponse {
+    pub command: String,
+    pub description: String,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AnsibleRequest {
+    #[serde(default = "default_play_name")]
+    pub play_name: String,
+    #[serde(default = "default_hosts")]
+    pub hosts: String,
+    #[serde(default)]
+    pub r#become: bool,
+    #[serde(default = "default_true")]
+    pub gather_facts: bool,
+    #[serde(default)]
+    pub vars: String,
+    #[serde(default)]
+    pub tasks: String,
+    #[serde(default)]
+    pub handlers: String,
+}
+
+impl Default for K8sRequest {
+    fn default() -> Self {
+        K8sRequest {
+            kind: default_kind(),
+            name: default_name(),
+            namespace: default_namespace(),
+            image: default_image(),
+            replicas: default_replicas(),
+            port: default_port(),
+            target_port: default_target_port(),
+            service_type: default_service_type(),
+            ingress_host: default_ingress_host(),
+            ingress_path: default_ingress_path(),
+            pull_policy: default_pull_policy(),
+            cpu_limit: None,
+            memory_limit: None,
+            cpu_request: None,
+            memory_request: None,
+            env: Vec::new(),
+            schedule: default_schedule(),
+            restart_policy: default_restart_policy(),
+        }
+    }
+}
+
+impl Default for AnsibleRequest {
+    fn default() -> Self {
+        AnsibleRequest {
+            play_name: default_play_name(),
+            hosts: default_hosts(),
+            r#become: false,
+            gather_facts: default_true(),
+            vars: String::new(),
+            tasks: String::new(),
+            handlers: String::new(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, JsonSchema)]
+pub struct K8sEnvVar {
+    pub key: String,
+    pub value: String,
+}
+
+fn default_kind() -> String {
+    "Deployment".to_string()
+}
+fn default_name() -> String {
+    "app-name".to_string()
+}
+fn default_namespace() -> String {
+    "default".to_string()
+}
+fn default_image() -> String {
+    "nginx:latest".to_string()
+}
+fn default_replicas() -> i32 {
+    1
+}
+fn default_port() -> i32 {
+    80
+}
+fn default_target_port() -> i32 {
+    80
+}
+fn default_service_type() -> String {
+    "ClusterIP".to_string()
+}
+fn default_ingress_host() -> String {
+    "example.com".to_string()
+}
+fn default_ingress_path() -> String {
+    "/".to_string()
+}
+fn default_pull_policy() -> String {
+    "IfNotPresent".to_string()
+}
+fn default_schedule() -> String {
+    "*/1 * * * *".to_string()
+}
+fn default_restart_policy() -> String {
+    "Always".to_string()
+}
+fn default_play_name() -> String {
+    "Ansible Playbook".to_string()
+}
+fn default_hosts() -> String {
+    "all".to_string()
+}
+fn default_resource_type() -> String {
+    "pod".to_string()
+}
+fn default_compose_restart() -> String {
+    "unless-stopped".to_string()
+}
+fn default_true() -> bool {
+    true
+}
+fn default_cidr() -> u8 {
+    24
+}
+fn default_base64_action() -> String {
+    "encode".to_string()
+}
@@ -0,0 +1,551 @@
+use crate::batch::{BatchItem, BatchResponse};
+use crate::error::ErrorEnvelope;
+use crate::health::{HealthResponse, SelftestResponse};
+use crate::i18n::{tr, Locale};
+use crate::live;
+use crate::models::WhoamiResponse;
+use crate::output::Format;
+use crate::pipeline::{PipelineRequest, PipelineResponse};
+#[cfg(feature = "devops")]
+use crate::scaffold::ScaffoldRequest;
+use crate::share::{ShareRequest, ShareResponse, Shared};
+use crate::tools::Group;
+use crate::toolset::ToolSet;
+use schemars::generate::SchemaSettings;
+use schemars::{Schema, SchemaGenerator};
+use serde_json::{json, Map, Value};
+
+// OpenAPI 3.1 document for every route and enabled tool. Schemas are derived from the models,
+// so serde renames, `default = ...` functions and the `enum` lists in models.rs all show up
+// here. Tool summaries are in the caller's language.
+pub fn document(tools: &ToolSet, locale: Locale) -> Value {
+    let mut gen = generator();
+
+    let error = gen.subschema_for::<ErrorEnvelope>();
+    let whoami = gen.subschema_for::<WhoamiResponse>();
+
+    let mut paths = Map::new();
+    paths.insert(
+        "/api/ping".to_string(),
+        json!({
+            "get": {
+                "operationId": "ping",
+                "summary": "Liveness check",
+                "security": [],
+                "responses": {
+                    "200": {
+                        "description": "Always `Pong`",
+                        "content": { "text/plain": { "schema": { "type": "string" } } }
+                    }
+                }
+            }
+        }),
+    );
+    paths.insert(
+        "/api/tools".to_string(),
+        json!({
+            "get": {
+                "operationId": "tools",
+                "summary": "Name, description and path of every tool this deployment serves",
+                "responses": {
+                    "200": {
+                        "description": "One entry per enabled tool",
+                        "content": { "application/json": { "schema": {
+                            "type": "array",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "name": { "type": "string" },
+                                    "description": { "type": "string" },
+                                    "path": { "type": "string" }
+                                },
+                                "required": ["name", "description", "path"]
+                            }
+                        } } }
+                    }
+                }
+            }
+        }),
+    );
+    paths.insert(
+        "/api/openapi.json".to_string(),
+        json!({
+            "get": {
+                "operationId": "openapi",
+                "summary": "This document",
+                "responses": {
+                    "200": {
+                        "description": "OpenAPI 3.1 description of the enabled routes",
+                        "content": { "application/json": { "schema": { "type": "object" } } }
+                    }
+                }
+            }
+        }),
+    );
+    paths.insert(
+        "/api/docs".to_string(),
+        json!({
+            "get": {
+                "operationId": "docs",
+                "summary": "API explorer page rendering this document",
+                "security": [],
+                "responses": {
+                    "200": {
+                        "description": "HTML page",
+                        "content": { "text/html": { "schema": { "type": "string" } } }
+                    }
+                }
+            }
+        }),
+    );
+    let health = gen.subschema_for::<HealthResponse>();
+    let selftest = gen.subschema_for::<SelftestResponse>();
+    paths.insert(
+        "/api/health".to_string(),
+        json!({
+            "get": {
+                "operationId": "health",
+                "summary": "Crate version, build timestamp and git SHA of this deployment",
+                "responses": { "200": json_response("Build information", &health) }
+            }
+        }),
+    );
+    paths.insert(
+        "/api/selftest".to_string(),
+        json!({
+            "get": {
+                "operationId": "selftest",
+                "summary": "Run every tool against built-in known-answer vectors",
+                "responses": {
+                    "200": json_response("All vectors passed", &selftest),
+                    "503": json_response("At least one vector failed; see `cases`", &selftest)
+                }
+            }
+        }),
+    );
+    paths.insert(
+        "/api/metrics".to_string(),
+        json!({
+            "get": {
+                "operationId": "metrics",
+                "summary": "Request counts and latency histograms per route, in Prometheus text format",
+                "responses": {
+                    "200": {
+                        "description": "`toolbox_requests_total` and `toolbox_request_duration_seconds` for this isolate",
+                        "content": { "text/plain": { "schema": { "type": "string" } } }
+                    }
+                }
+            }
+        }),
+    );
+    paths.insert(
+        "/api/whoami".to_string(),
+        json!({
+            "post": {
+                "operationId": "whoami",
+                "summary": "Echo the caller's IP, location and request headers",
+                "responses": { "200": json_response("Caller information", &whoami) }
+            }
+        }),
+    );
+
+    let batch_request = gen.subschema_for::<Vec<BatchItem>>();
+    let batch_response = gen.subschema_for::<BatchResponse>();
+    paths.insert(
+        "/api/batch".to_string(),
+        json!({
+            "post": {
+                "operationId": "batch",
+                "summary": "Run several tool calls in one request; results come back in order",
+                "requestBody": {
+                    "required": true,
+                    "content": { "application/json": { "schema": batch_request } }
+                },
+                "responses": {
+                    "200": json_response("Per-item results; failed items carry `status` and `error`", &batch_response),
+                    "400": json_response("Body is not a JSON array of `{tool, input}` objects", &error),
+                    "413": json_response("More items than MAX_BATCH_ITEMS, or the body exceeds a size limit", &error)
+                }
+            }
+        }),
+    );
+
+    let pipeline_request = gen.subschema_for::<PipelineRequest>();
+    let pipeline_response = gen.subschema_for::<PipelineResponse>();
+    paths.insert(
+        "/api/pipeline".to_string(),
+        json!({
+            "post": {
+                "operationId": "pipeline",
+                "summary": "Chain tools, feeding each step's output into the next",
+                "requestBody": {
+                    "required": true,
+                    "content": { "application/json": { "schema": pipeline_request } }
+                },
+                "responses": {
+                    "200": json_response("Final result and every intermediate step", &pipeline_response),
+                    "4XX": json_response("A step failed; `error` is set and `steps` ends with the failing step", &pipeline_response),
+                    "413": json_response("More steps than MAX_PIPELINE_STEPS, or the body exceeds a size limit", &error)
+                }
+            }
+        }),
+    );
+
+    paths.insert(
+        "/api/mcp".to_string(),
+        json!({
+            "post": {
+                "operationId": "mcp",
+                "summary": "JSON-RPC 2.0 endpoint with the Model Context Protocol methods `initialize`, `ping`, `tools/list` and `tools/call`",
+                "requestBody": {
+                    "required": true,
+                    "content": { "application/json": { "schema": { "type": ["object", "array"] } } }
+                },
+                "responses": {
+                    "200": {
+                        "description": "JSON-RPC response, or an array of them for a batch; tool failures are results with `isError: true`",
+                        "content": { "application/json": { "schema": { "type": ["object", "array"] } } }
+                    },
+                    "202": { "description": "The body held only notifications" }
+                }
+            }
+        }),
+    );
+
+    paths.insert(
+        "/api/live/{tool}".to_string(),
+        json!({
+            "get": {
+                "operationId": "live",
+                "summary": "WebSocket session for `regex`, `diff`, `sql` or `json`. Send `{\"seq\", \"set\": {field: value}, \"edits\": [{\"field\", \"start\", \"end\", \"text\"}]}` with increasing `seq`; each is answered with `{\"seq\", \"result\"}` or `{\"seq\", \"error\"}`. Edit offsets count UTF-16 code units. Opening the socket and every update take a token from the tool's rate limit.",
+                "parameters": [{ "name": "tool", "in": "path", "required": true, "schema": { "type": "string", "enum": live::TOOLS } }],
+                "responses": {
+                    "101": { "description": "Switched to the WebSocket protocol" },
+                    "404": json_response("Unknown or disabled tool, or one without live sessions", &error),
+                    "429": json_response("Rate limit for this tool used up; see Retry-After", &error),
+                    "426": json_response("The request was not a WebSocket upgrade", &error)
+                }
+            }
+        }),
+    );
+
+    let share_request = gen.subschema_for::<ShareRequest>();
+    let share_response = gen.subschema_for::<ShareResponse>();
+    let shared = gen.subschema_for::<Shared>();
+    paths.insert(
+        "/api/share".to_string(),
+        json!({
+            "post": {
+                "operationId": "share",
+                "summary": "Save a tool's input under a short ID; `/s/{id}` reopens the tool with it",
+                "requestBody": {
+                    "required": true,
+                    "content": { "application/json": { "schema": share_request } }
+                },
+                "responses": {
+                    "201": json_response("The share ID, its link and when it expires", &share_response),
+                    "404": json_response("Unknown or disabled tool", &error)
+                }
+            }
+        }),
+    );
+    paths.insert(
+        "/s/{id}".to_string(),
+        json!({
+            "get": {
+                "operationId": "share-open",
+                "summary": "Share link; redirects to the homepage, which loads the saved tool and input",
+                "security": [],
+                "parameters": [{ "name": "id", "in": "path", "required": true, "schema": { "type": "string" } }],
+                "responses": {
+                    "302": { "description": "Redirect to `/#s={id}`" }
+                }
+            }
+        }),
+    );
+    paths.insert(
+        "/api/share/{id}".to_string(),
+        json!({
+            "get": {
+                "operationId": "share-get",
+                "summary": "The tool and input saved under a share ID",
+                "parameters": [{ "name": "id", "in": "path", "required": true, "schema": { "type": "string" } }],
+                "responses": {
+                    "200": json_response("Saved tool and input", &shared),
+                    "404": json_response("No such share, or it has expired", &error)
+                }
+            }
+        }),
+    );
+
+    // The scaffold bundles the devops generators
+    #[cfg(feature = "devops")]
+    {
+        let scaffold_request = gen.subschema_for::<ScaffoldRequest>();
+        let archive = json!({
+            "description": "`<name>.zip` or `<name>.tar` with Dockerfile, docker-compose.yml, k8s manifests, nginx.conf and an Ansible playbook",
+            "headers": {
+                "Content-Disposition": {
+                    "description": "`attachment; filename=\"...\"`",
+                    "schema": { "type": "string" }
+                }
+            },
+            "content": {
+                "application/zip": { "schema": { "type": "string", "contentMediaType": "application/zip" } },
+                "application/x-tar": { "schema": { "type": "string", "contentMediaType": "application/x-tar" } }
+            }
+        });
+        let scaffold_responses = error_responses(archive, &error);
+        paths.insert(
+            "/api/scaffold".to_string(),
+            json!({
+                "get": {
+                    "operationId": "scaffold-get",
+                    "summary": "Project bundle for one service (scalar fields only)",
+                    "tags": [Group::Devops.as_str()],
+                    "parameters": query_parameters(&gen, &scaffold_request),
+                    "responses": scaffold_responses
+                },
+                "post": {
+                    "operationId": "scaffold",
+                    "summary": "Project bundle for one service",
+                    "tags": [Group::Devops.as_str()],
+                    "requestBody": {
+                        "required": true,
+                        "content": { "application/json": { "schema": scaffold_request } }
+                    },
+                    "responses": scaffold_responses
+                }
+            }),
+        );
+    }
+
+    for tool in tools.tools() {
+        let request = tool.request_schema(&mut gen);
+        let response = tool.response_schema(&mut gen);
+        let mut content = json!({
+            "application/json": { "schema": response },
+            "application/yaml": { "schema": response },
+            "application/toml": { "schema": response },
+            "text/plain": { "schema": { "type": "string" } }
+        });
+        if let Some(media_type) = tool.media_type() {
+            content[media_type] = json!({ "schema": { "type": "string" } });
+        }
+        let ok = json!({
+            "description": "Tool output, negotiated via `format` or Accept; `raw` serves the generated document",
+            "content": content
+        });
+        let responses = error_responses(ok, &error);
+        let mut get_parameters = query_parameters(&gen, &request);
+        // A tool field called `format` (ps) takes the query parameter; use Accept there
+        if !get_parameters.iter().any(|p| p["name"] == "format") {
+            get_parameters.push(format_parameter());
+        }
+        get_parameters.push(lang_parameter());
+        paths.insert(
+            tool.path(),
+            json!({
+                "get": {
+                    "operationId": format!("{}-get", tool.name()),
+                    "summary": tool.description(locale),
+                    "tags": [tool.group().as_str()],
+                    "parameters": get_parameters,
+                    "responses": responses
+                },
+                "post": {
+                    "operationId": tool.name(),
+                    "summary": tool.description(locale),
+                    "tags": [tool.group().as_str()],
+                    "parameters": [format_parameter(), lang_parameter()],
+                    "requestBody": {
+                        "required": true,
+                        "content": { "application/json": { "schema": request } }
+                    },
+                    "responses": responses
+                }
+            }),
+        );
+
+        if let Some(filename) = tool.default_filename() {
+            let mut content = json!({});
+            content[tool.media_type().unwrap_or("application/octet-stream")] =
+                json!({ "schema": { "type": "string" } });
+            let file = json!({
+                "description": format!("The generated file, e.g. `{}`", filename),
+                "headers": {
+                    "Content-Disposition": {
+                        "description": "`attachment; filename=\"...\"`",
+                        "schema": { "type": "string" }
+                    }
+                },
+                "content": content
+            });
+            let file_responses = error_responses(file, &error);
+            paths.insert(
+                format!("{}/download", tool.path()),
+                json!({
+                    "get": {
+                        "operationId": format!("{}-download-get", tool.name()),
+                        "summary": tr(locale, "openapi.download", &[&tool.description(locale)]),
+                        "tags": [tool.group().as_str()],
+                        "parameters": query_parameters(&gen, &request),
+                        "responses": file_responses
+                    },
+                    "post": {
+                        "operationId": format!("{}-download", tool.name()),
+                        "summary": tr(locale, "openapi.download", &[&tool.description(locale)]),
+                        "tags": [tool.group().as_str()],
+                        "requestBody": {
+                            "required": true,
+                            "content": { "application/json": { "schema": request } }
+                        },
+                        "responses": file_responses
+                    }
+                }),
+            );
+        }
+
+        if tool.streams() {
+            let ok = json!({
+                "description": "One row per line as NDJSON, or CSV with a header line; `?format=ndjson|csv` or Accept picks which",
+                "content": {
+                    "application/x-ndjson": { "schema": { "type": "string" } },
+                    "text/csv": { "schema": { "type": "string" } }
+                }
+            });
+            let rows = error_responses(ok, &error);
+            paths.insert(
+                format!("{}/stream", tool.path()),
+                json!({
+                    "get": {
+                        "operationId": format!("{}-stream-get", tool.name()),
+                        "summary": tr(locale, "openapi.stream", &[&tool.description(locale)]),
+                        "tags": [tool.group().as_str()],
+                        "parameters": query_parameters(&gen, &request),
+                        "responses": rows
+                    },
+                    "post": {
+                        "operationId": format!("{}-stream", tool.name()),
+                        "summary": tr(locale, "openapi.stream", &[&tool.description(locale)]),
+                        "tags": [tool.group().as_str()],
+                        "requestBody": {
+                            "required": true,
+                            "content": { "application/json": { "schema": request } }
+                        },
+                        "responses": rows
+                    }
+                }),
+            );
+        }
+    }
+
+    let tags: Vec<Value> = Group::ALL
+        .iter()
+        .map(|g| json!({ "name": g.as_str() }))
+        .collect();
+
+    json!({
+        "openapi": "3.1.0",
+        "info": {
+            "title": "Rust Toolbox API",
+            "version": env!("CARGO_PKG_VERSION")
+        },
+        "tags": tags,
+        // Only enforced when the deployment sets API_KEYS
+        "security": [{ "bearer": [] }, { "apiKey": [] }],
+        "paths": paths,
+        "components": {
+            "schemas": gen.take_definitions(true),
+            "securitySchemes": {
+                "bearer": { "type": "http", "scheme": "bearer" },
+                "apiKey": { "type": "apiKey", "in": "header", "name": "X-API-Key" }
+            }
+        }
+    })
+}
+
+// Schemas land under `components.schemas`, where `$ref`s point.
+pub fn generator() -> SchemaGenerator {
+    let mut settings = SchemaSettings::draft2020_12();
+    settings.definitions_path = "/components/schemas".into();
+    settings.meta_schema = None;
+    settings.into_generator()
+}
+
+fn lang_parameter() -> Value {
+    json!({
+        "name": "lang",
+        "in": "query",
+        "required": false,
+        "description": "Language of descriptions and error messages; overrides Accept-Language",
+        "schema": { "type": "string", "enum": ["en", "zh-CN"] }
+    })
+}
+
+fn format_parameter() -> Value {
+    json!({
+        "name": "format",
+        "in": "query",
+        "required": false,
+        "description": "Response format; overrides the Accept header",
+        "schema": { "type": "string", "enum": Format::NAMES }
+    })
+}
+
+// Scalar fields of a request model as optional query parameters; nested fields are POST-only.
+pub fn query_parameters(gen: &SchemaGenerator, request: &Schema) -> Vec<Value> {
+    let name = request
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|r| r.rsplit('/').next())
+        .unwrap_or_default();
+    let definition = match gen.definitions().get(name) {
+        Some(definition) => definition,
+        None => return Vec::new(),
+    };
+    let required: Vec<&Value> = definition
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|r| r.iter().collect())
+        .unwrap_or_default();
+    definition
+        .get("properties")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .filter(|(_, schema)| match schema.get("type") {
+            Some(Value::String(t)) => t != "array" && t != "object",
+            Some(Value::Array(types)) => types.iter().all(|t| t != "array" && t != "object"),
+            _ => false,
+        })
+        .map(|(field, schema)| {
+            json!({
+                "name": field,
+                "in": "query",
+                "required": required.contains(&&Value::String(field.clone())),
+                "schema": schema
+            })
+        })
+        .collect()
+}
+
+// `ok` as the 200 response, followed by the errors every tool route can answer with.
+fn error_responses(ok: Value, error: &Schema) -> Value {
+    json!({
+        "200": ok,
+        "400": json_response("Input does not match the request schema", error),
+        "401": json_response("Missing or unknown API key", error),
+        "403": json_response("The API key lacks the scope for this tool group", error),
+        "413": json_response("Body, query string or a string value exceeds a size limit", error),
+        "429": json_response("Rate limit for this route used up; see Retry-After", error),
+        "422": json_response("A field value was rejected; `error.field` names it", error)
+    })
+}
+
+fn json_response(description: &str, schema: &Schema) -> Value {
+    json!({
+        "description": description,
+        "content": { "application/json": { "schema": schema } }
+    })
+}
@@ -0,0 +1,149 @@
+use crate::error::ToolError;
+use serde_json::Value;
+
+// How a tool response is written back. `Raw` serves the generated document itself (manifest,
+// config file, SVG) for tools that declare a `MEDIA_TYPE`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Yaml,
+    Toml,
+    Text,
+    Raw,
+}
+
+impl Format {
+    pub const NAMES: [&'static str; 5] = ["json", "yaml", "toml", "text", "raw"];
+
+    pub fn from_name(name: &str) -> Option<Format> {
+        let format = match name {
+            "json" => Some(Format::Json),
+            "yaml" | "yml" => Some(Format::Yaml),
+            "toml" => Some(Format::Toml),
+            "text" | "txt" => Some(Format::Text),
+            "raw" => Some(Format::Raw),
+            _ => None,
+        };
+        format.filter(|f| f.is_available())
+    }
+
+    // YAML and TOML output needs the serializers of the `formats` feature.
+    fn is_available(self) -> bool {
+        cfg!(feature = "formats") || !matches!(self, Format::Yaml | Format::Toml)
+    }
+
+    // A recognised `format` parameter wins; otherwise the first Accept entry this API can
+    // produce decides. Clients that send nothing or `*/*` (browsers, plain curl) get JSON.
+    pub fn negotiate(param: Option<&str>, accept: &str, media_type: Option<&str>) -> Format {
+        if let Some(format) = param.and_then(Format::from_name) {
+            return format;
+        }
+        for media in accept.split(',') {
+            let media = media.split(';').next().unwrap_or("").trim();
+            if Some(media) == media_type {
+                return Format::Raw;
+            }
+            let format = match media {
+                "application/json" | "*/*" => Format::Json,
+                "application/yaml" | "application/x-yaml" | "text/yaml" | "text/x-yaml" => {
+                    Format::Yaml
+                }
+                "application/toml" => Format::Toml,
+                "text/plain" => Format::Text,
+                _ => continue,
+            };
+            if format.is_available() {
+                return format;
+            }
+        }
+        Format::Json
+    }
+
+    fn content_type(self) -> &'static str {
+        match self {
+            Format::Json => "application/json",
+            Format::Yaml => "application/yaml",
+            Format::Toml => "application/toml",
+            Format::Text | Format::Raw => "text/plain; charset=utf-8",
+        }
+    }
+}
+
+pub struct Rendered {
+    pub body: String,
+    pub content_type: &'static str,
+}
+
+pub fn render(
+    value: &Value,
+    format: Format,
+    media_type: Option<&'static str>,
+) -> Result<Rendered, ToolError> {
+    // Asking a YAML generator for YAML means the manifest itself, not `result: |` around it
+    if let (Some(media_type), Some(doc)) = (media_type, document(value)) {
+        if format == Format::Raw || format.content_type() == media_type {
+            return Ok(Rendered {
+                body: doc.to_string(),
+                content_type: media_type,
+            });
+        }
+    }
+
+    let body = match format {
+        Format::Json => {
+            serde_json::to_string(value).map_err(|e| ToolError::Internal(e.to_string()))?
+        }
+        #[cfg(feature = "formats")]
+        Format::Yaml => serde_yaml::to_string(value)
+            .map_err(|e| ToolError::invalid("format", "output.cannot_render", &[&"YAML", &e]))?,
+        #[cfg(feature = "formats")]
+        Format::Toml => toml::to_string(value)
+            .map_err(|e| ToolError::invalid("format", "output.cannot_render", &[&"TOML", &e]))?,
+        #[cfg(not(feature = "formats"))]
+        Format::Yaml | Format::Toml => {
+            return Err(ToolError::invalid("format", "output.not_built", &[]))
+        }
+        Format::Text | Format::Raw => to_plain_text(value),
+    };
+    Ok(Rendered {
+        body,
+        content_type: format.content_type(),
+    })
+}
+
+// The generated document of a single-field response such as `{"result": "..."}`.
+pub fn document(value: &Value) -> Option<&str> {
+    match value {
+        Value::Object(map) if map.len() == 1 => map.values().next().and_then(Value::as_str),
+        _ => None,
+    }
+}
+
+// Renders a tool response for shell use: single-field objects are unwrapped, arrays give one
+// line per item (objects as tab-separated values) and other objects give `key: value` lines.
+pub fn to_plain_text(value: &Value) -> String {
+    let mut text = match value {
+        Value::Object(map) if map.len() == 1 => return to_plain_text(map.values().next().unwrap()),
+        Value::Array(items) => items.iter().map(row).collect::<Vec<_>>().join("\n"),
+        Value::Object(map) => map
+            .iter()
+            .map(|(k, v)| format!("{}: {}", k, row(v)))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        other => row(other),
+    };
+    if !text.ends_with('\n') {
+        text.push('\n');
+    }
+    text
+}
+
+fn row(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        Value::Array(items) => items.iter().map(row).collect::<Vec<_>>().join(", "),
+        Value::Object(map) => map.values().map(row).collect::<Vec<_>>().join("\t"),
+        other => other.to_string(),
+    }
+}
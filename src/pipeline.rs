@@ -0,0 +1,313 @@
+use crate::error::{ErrorBody, ToolError};
+use crate::tools::{self, Context, Input};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::future::Future;
+
+#[derive(Deserialize, JsonSchema)]
+pub struct PipelineRequest {
+    // Value piped into the first step; omit it to start with a step that only uses `args`
+    #[serde(default)]
+    pub input: Value,
+    pub steps: Vec<PipelineStep>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct PipelineStep {
+    pub tool: String,
+    // Remaining request fields of the tool, e.g. `{"action": "decode"}` for base64
+    #[serde(default)]
+    pub args: Map<String, Value>,
+    // Field name or JSON pointer into the previous output; defaults to the tool's pipe output
+    pub select: Option<String>,
+    // Field name or JSON pointer into this step's request; defaults to the tool's pipe input
+    pub into: Option<String>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct PipelineStepResult {
+    pub tool: String,
+    pub status: u16,
+    pub input: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<Value>,
+}
+
+// On failure `error` is set and `steps` ends with the failing step, so the body is still a
+// valid error envelope while keeping the intermediate results.
+#[derive(Serialize, JsonSchema)]
+pub struct PipelineResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    pub steps: Vec<PipelineStepResult>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorBody>,
+}
+
+impl PipelineResponse {
+    pub fn status(&self) -> u16 {
+        self.steps.last().map_or(200, |s| s.status)
+    }
+}
+
+// `charge` takes a token from a step's rate limit before it runs, as a call to `/api/<tool>`
+// would; a refused step fails like any other.
+pub async fn run<F, Fut>(
+    req: PipelineRequest,
+    ctx: &Context,
+    mut charge: F,
+) -> Result<PipelineResponse, ToolError>
+where
+    F: FnMut(&'static str) -> Fut,
+    Fut: Future<Output = Result<(), ToolError>>,
+{
+    if req.steps.is_empty() {
+        return Err(ToolError::invalid("steps", "pipeline.empty", &[]));
+    }
+    ctx.limits.pipeline_steps(req.steps.len())?;
+
+    let mut steps = Vec::new();
+    let mut piped = req.input;
+    // Pipe output of the previous tool, applied when the next step has no `select`
+    let mut prev_output: Option<&'static str> = None;
+
+    for (i, step) in req.steps.into_iter().enumerate() {
+        let mut input = Value::Object(step.args);
+        let outcome = match pipe(
+            i,
+            &step.tool,
+            step.select,
+            step.into,
+            prev_output,
+            piped,
+            &mut input,
+        ) {
+            Ok(tool) => match charge(tool.name()).await {
+                Ok(()) => tool
+                    .call(Input::Json(input.clone()), ctx)
+                    .map(|output| (tool, output)),
+                Err(e) => Err(e),
+            },
+            Err(e) => Err(e),
+        };
+
+        match outcome {
+            Ok((tool, output)) => {
+                steps.push(PipelineStepResult {
+                    tool: step.tool,
+                    status: 200,
+                    input,
+                    output: Some(output.clone()),
+                });
+                piped = output;
+                prev_output = tool.pipe_output();
+            }
+            Err(e) => {
+                steps.push(PipelineStepResult {
+                    tool: step.tool,
+                    status: e.status(),
+                    input,
+                    output: None,
+                });
+                return Ok(PipelineResponse {
+                    result: None,
+                    steps,
+                    error: Some(e.envelope(ctx.locale).error),
+                });
+            }
+        }
+    }
+
+    Ok(PipelineResponse {
+        result: pick(piped, prev_output),
+        steps,
+        error: None,
+    })
+}
+
+// Resolves step `i`'s tool and writes the selected part of `piped` into its request. Only the
+// first step may start from nothing; a `null` from an earlier step is an error, not an input.
+fn pipe(
+    i: usize,
+    name: &str,
+    select: Option<String>,
+    into: Option<String>,
+    prev_output: Option<&'static str>,
+    piped: Value,
+    input: &mut Value,
+) -> Result<&'static dyn tools::DynTool, ToolError> {
+    let tool = tools::find(name).ok_or_else(|| ToolError::unknown_tool(name))?;
+    // Steps are numbered from 1 in messages
+    let n = i + 1;
+
+    let sel = select.as_deref().or(prev_output);
+    let value = pick(piped, sel).ok_or_else(|| {
+        ToolError::invalid(
+            "select",
+            "pipeline.no_field",
+            &[&n, &sel.unwrap_or_default()],
+        )
+    })?;
+    if value.is_null() {
+        if i == 0 {
+            return Ok(tool);
+        }
+        return Err(ToolError::invalid(
+            "select",
+            "pipeline.null",
+            &[&n, &sel.unwrap_or("output")],
+        ));
+    }
+
+    let target = into
+        .as_deref()
+        .or_else(|| tool.pipe_input())
+        .ok_or_else(|| ToolError::invalid("into", "pipeline.needs_into", &[&n, &name]))?;
+    if !set_pointer(input, &to_pointer(target), value) {
+        return Err(ToolError::invalid(
+            "into",
+            "pipeline.bad_into",
+            &[&n, &target],
+        ));
+    }
+    Ok(tool)
+}
+
+// The part of a step's output that is piped on: the selected field, the only field of a
+// single-field response such as `{"result": ...}`, or else the whole output.
+fn pick(output: Value, select: Option<&str>) -> Option<Value> {
+    match select {
+        Some(sel) => output.pointer(&to_pointer(sel)).cloned(),
+        None => match output {
+            Value::Object(ref map) if map.len() == 1 => map.values().next().cloned(),
+            other => Some(other),
+        },
+    }
+}
+
+fn to_pointer(path: &str) -> String {
+    if path.starts_with('/') {
+        path.to_string()
+    } else {
+        format!("/{}", path)
+    }
+}
+
+// Like `Value::pointer_mut`, but also creates the last object key or appends to an array.
+fn set_pointer(target: &mut Value, pointer: &str, value: Value) -> bool {
+    let idx = match pointer.rfind('/') {
+        Some(idx) => idx,
+        None => return false,
+    };
+    let key = pointer[idx + 1..].replace("~1", "/").replace("~0", "~");
+    match target.pointer_mut(&pointer[..idx]) {
+        Some(Value::Object(map)) => {
+            map.insert(key, value);
+            true
+        }
+        Some(Value::Array(items)) => match key.parse::<usize>() {
+            Ok(n) if n < items.len() => {
+                items[n] = value;
+                true
+            }
+            Ok(n) if n == items.len() => {
+                items.push(value);
+                true
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ratelimit::testing::{block_on, charge};
+    use serde_json::json;
+
+    fn pipeline(request: Value) -> PipelineResponse {
+        let request: PipelineRequest = serde_json::from_value(request).unwrap();
+        block_on(run(request, &Context::default(), charge(10))).unwrap()
+    }
+
+    #[cfg(all(feature = "crypto", feature = "formats"))]
+    #[test]
+    fn chains_base64_into_json() {
+        let response = pipeline(json!({
+            "input": "eyJhIjoxfQ==",
+            "steps": [{ "tool": "base64", "args": { "action": "decode" } }, { "tool": "json" }]
+        }));
+        assert!(response.error.is_none());
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.steps.len(), 2);
+        assert_eq!(response.steps[1].input, json!({ "input": "{\"a\":1}" }));
+        assert_eq!(response.result, Some(json!("{\n  \"a\": 1\n}")));
+    }
+
+    #[cfg(all(feature = "crypto", feature = "formats"))]
+    #[test]
+    fn stops_at_a_failing_middle_step() {
+        let response = pipeline(json!({
+            "input": "bm90IGpzb24=",
+            "steps": [
+                { "tool": "base64", "args": { "action": "decode" } },
+                { "tool": "json" },
+                { "tool": "md5" }
+            ]
+        }));
+        assert_eq!(response.steps.len(), 2);
+        assert_eq!(response.steps[0].status, 200);
+        assert_eq!(response.status(), response.steps[1].status);
+        assert_ne!(response.status(), 200);
+        assert!(response.result.is_none());
+        assert!(response.error.is_some());
+    }
+
+    #[test]
+    fn reports_unknown_tools() {
+        let response = pipeline(json!({ "steps": [{ "tool": "no-such-tool" }] }));
+        assert_eq!(response.status(), 404);
+        assert_eq!(response.error.unwrap().code, "not_found");
+        let empty = serde_json::from_value(json!({ "steps": [] })).unwrap();
+        assert!(block_on(run(empty, &Context::default(), charge(10))).is_err());
+    }
+
+    #[cfg(feature = "crypto")]
+    #[test]
+    fn steps_over_the_tool_budget_are_rate_limited() {
+        let request = json!({
+            "input": "a",
+            "steps": [{ "tool": "md5" }, { "tool": "md5" }, { "tool": "md5" }]
+        });
+        let request: PipelineRequest = serde_json::from_value(request).unwrap();
+        let response = block_on(run(request, &Context::default(), charge(2))).unwrap();
+        assert_eq!(response.steps.len(), 3);
+        assert_eq!(response.status(), 429);
+        assert_eq!(response.error.unwrap().code, "rate_limited");
+    }
+
+    #[cfg(feature = "crypto")]
+    #[test]
+    fn numbers_steps_from_one_and_reports_null_outputs() {
+        use crate::i18n::Locale;
+        let fail = |i, select: &str, piped| {
+            let select = Some(select.to_string());
+            match pipe(i, "md5", select, None, None, piped, &mut json!({})) {
+                Ok(_) => panic!("step {} was piped", i),
+                Err(e) => e.message(Locale::En),
+            }
+        };
+        assert_eq!(
+            fail(1, "a", json!({ "a": null })),
+            "Step 2: previous step's 'a' is null"
+        );
+        assert_eq!(
+            fail(2, "b", json!({ "a": 1 })),
+            "Step 3: previous output has no 'b'"
+        );
+        let mut input = json!({});
+        assert!(pipe(0, "md5", None, None, None, Value::Null, &mut input).is_ok());
+    }
+}
@@ -0,0 +1,295 @@
+use ipnetwork::IpNetwork;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::IpAddr;
+
+// Buckets above this count are pruned of idle (full) entries before a new one is added.
+const MAX_BUCKETS: usize = 10_000;
+
+// `capacity` requests per `period_secs`, refilled continuously; written as `10/60`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Budget {
+    pub capacity: u32,
+    pub period_secs: u32,
+}
+
+impl Budget {
+    pub fn parse(s: &str) -> Option<Budget> {
+        let (capacity, period) = s.trim().split_once('/')?;
+        let budget = Budget {
+            capacity: capacity.trim().parse().ok()?,
+            period_secs: period.trim().parse().ok()?,
+        };
+        if budget.capacity == 0 || budget.period_secs == 0 {
+            return None;
+        }
+        Some(budget)
+    }
+
+    fn per_ms(self) -> f64 {
+        self.capacity as f64 / (self.period_secs as f64 * 1000.0)
+    }
+}
+
+// Which routes are limited and who is exempt. Routes are the first path segment after
+// `/api/`, so `/api/qrcode/download` shares the `qrcode` budget.
+pub struct RateLimitConfig {
+    default: Option<Budget>,
+    routes: Vec<(String, Budget)>,
+    allowlist: Vec<IpNetwork>,
+}
+
+impl RateLimitConfig {
+    // Raw var values: a default budget (`120/60`; empty disables limiting for unlisted
+    // routes), comma-separated `route=budget` pairs and comma-separated IPs or CIDRs.
+    // Entries that do not parse are ignored.
+    pub fn new(default: Option<String>, routes: Option<String>, allowlist: Option<String>) -> Self {
+        RateLimitConfig {
+            default: default.as_deref().and_then(Budget::parse),
+            routes: routes
+                .unwrap_or_default()
+                .split(',')
+                .filter_map(|pair| {
+                    let (route, budget) = pair.split_once('=')?;
+                    Some((route.trim().to_string(), Budget::parse(budget)?))
+                })
+                .collect(),
+            allowlist: allowlist
+                .unwrap_or_default()
+                .split(',')
+                .filter_map(|entry| entry.trim().parse().ok())
+                .collect(),
+        }
+    }
+
+    pub fn budget(&self, route: &str) -> Option<Budget> {
+        self.routes
+            .iter()
+            .find(|(r, _)| r == route)
+            .map(|(_, b)| *b)
+            .or(self.default)
+    }
+
+    pub fn is_allowlisted(&self, ip: &str) -> bool {
+        match ip.parse::<IpAddr>() {
+            Ok(ip) => self.allowlist.iter().any(|net| net.contains(ip)),
+            Err(_) => false,
+        }
+    }
+}
+
+// Bucket of a path: its tool, also for the tool's live session at `/api/live/<tool>`.
+pub fn route(path: &str) -> &str {
+    let rest = path.trim_start_matches("/api/");
+    let rest = rest.strip_prefix("live/").unwrap_or(rest);
+    rest.split('/').next().unwrap_or(rest)
+}
+
+// What a limiter is asked: take one token from `key`'s bucket under `budget`.
+#[derive(Serialize, Deserialize)]
+pub struct Take {
+    pub key: String,
+    pub budget: Budget,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Decision {
+    pub allowed: bool,
+    pub limit: u32,
+    pub remaining: u32,
+    // Seconds until the bucket is full again
+    pub reset: u64,
+    // Seconds until the next token, when the request was refused
+    pub retry_after: u64,
+}
+
+impl Decision {
+    pub fn headers(&self) -> Vec<(&'static str, String)> {
+        let mut out = vec![
+            ("X-RateLimit-Limit", self.limit.to_string()),
+            ("X-RateLimit-Remaining", self.remaining.to_string()),
+            ("X-RateLimit-Reset", self.reset.to_string()),
+        ];
+        if !self.allowed {
+            out.push(("Retry-After", self.retry_after.to_string()));
+        }
+        out
+    }
+}
+
+struct Bucket {
+    budget: Budget,
+    tokens: f64,
+    updated_ms: u64,
+}
+
+impl Bucket {
+    fn refill(&mut self, now_ms: u64) {
+        let elapsed = now_ms.saturating_sub(self.updated_ms) as f64;
+        self.tokens =
+            (self.tokens + elapsed * self.budget.per_ms()).min(self.budget.capacity as f64);
+        self.updated_ms = now_ms;
+    }
+
+    fn is_full(&self) -> bool {
+        self.tokens >= self.budget.capacity as f64
+    }
+}
+
+// Token buckets held in memory. Used directly when no Durable Object is bound (local dev)
+// and as the state of the `RateLimiter` Durable Object, which owns one client's buckets.
+#[derive(Default)]
+pub struct MemoryLimiter {
+    buckets: HashMap<String, Bucket>,
+}
+
+impl MemoryLimiter {
+    pub fn take(&mut self, take: &Take, now_ms: u64) -> Decision {
+        let budget = take.budget;
+        if !self.buckets.contains_key(&take.key) && self.buckets.len() >= MAX_BUCKETS {
+            self.prune(now_ms);
+        }
+        let bucket = self
+            .buckets
+            .entry(take.key.clone())
+            .or_insert_with(|| Bucket {
+                budget,
+                tokens: budget.capacity as f64,
+                updated_ms: now_ms,
+            });
+        bucket.refill(now_ms);
+        // A changed configuration applies from now on
+        bucket.budget = budget;
+        bucket.tokens = bucket.tokens.min(budget.capacity as f64);
+
+        let allowed = bucket.tokens >= 1.0;
+        if allowed {
+            bucket.tokens -= 1.0;
+        }
+        let secs_for = |tokens: f64| (tokens.max(0.0) / budget.per_ms() / 1000.0).ceil() as u64;
+        Decision {
+            allowed,
+            limit: budget.capacity,
+            remaining: bucket.tokens.floor() as u32,
+            reset: secs_for(budget.capacity as f64 - bucket.tokens),
+            retry_after: if allowed {
+                0
+            } else {
+                secs_for(1.0 - bucket.tokens).max(1)
+            },
+        }
+    }
+
+    // Drops buckets that have refilled completely; they behave exactly like new ones.
+    fn prune(&mut self, now_ms: u64) {
+        self.buckets.retain(|_, bucket| {
+            bucket.refill(now_ms);
+            !bucket.is_full()
+        });
+    }
+}
+
+// For the tests of modules that charge the tools they dispatch.
+#[cfg(test)]
+pub mod testing {
+    use super::*;
+    use crate::error::ToolError;
+    use std::future::{ready, Future, Ready};
+    use std::task::{Context, Poll, Waker};
+
+    // `capacity` calls per tool that never refill.
+    pub fn charge(capacity: u32) -> impl FnMut(&'static str) -> Ready<Result<(), ToolError>> {
+        let mut limiter = MemoryLimiter::default();
+        let budget = Budget {
+            capacity,
+            period_secs: u32::MAX,
+        };
+        move |tool| {
+            let take = Take {
+                key: tool.to_string(),
+                budget,
+            };
+            let decision = limiter.take(&take, 0);
+            ready(if decision.allowed {
+                Ok(())
+            } else {
+                Err(ToolError::RateLimited {
+                    route: tool.to_string(),
+                    retry_after: decision.retry_after,
+                })
+            })
+        }
+    }
+
+    // Runs a future that never waits, which holds for these in-memory charges.
+    pub fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = std::pin::pin!(future);
+        match future
+            .as_mut()
+            .poll(&mut Context::from_waker(Waker::noop()))
+        {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("future is waiting"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn take(key: &str, budget: &str) -> Take {
+        Take {
+            key: key.to_string(),
+            budget: Budget::parse(budget).unwrap(),
+        }
+    }
+
+    #[test]
+    fn bucket_runs_out_and_refills() {
+        let mut limiter = MemoryLimiter::default();
+        let t = take("1.2.3.4:diff", "3/60");
+        for remaining in [2, 1, 0] {
+            let d = limiter.take(&t, 0);
+            assert!(d.allowed);
+            assert_eq!(d.remaining, remaining);
+        }
+        let refused = limiter.take(&t, 0);
+        assert!(!refused.allowed);
+        assert_eq!(refused.retry_after, 20);
+        assert_eq!(refused.headers().last().unwrap().0, "Retry-After");
+
+        // One token every 20 seconds
+        assert!(!limiter.take(&t, 19_999).allowed);
+        assert!(limiter.take(&t, 20_000).allowed);
+        assert!(!limiter.take(&t, 20_000).allowed);
+        // Never more than the capacity, however long the client was away
+        assert_eq!(limiter.take(&t, 3_600_000).remaining, 2);
+    }
+
+    #[test]
+    fn buckets_are_per_key() {
+        let mut limiter = MemoryLimiter::default();
+        assert!(limiter.take(&take("a", "1/60"), 0).allowed);
+        assert!(!limiter.take(&take("a", "1/60"), 0).allowed);
+        assert!(limiter.take(&take("b", "1/60"), 0).allowed);
+    }
+
+    #[test]
+    fn config_parses_budgets_routes_and_allowlist() {
+        assert_eq!(Budget::parse("0/60"), None);
+        assert_eq!(Budget::parse("10"), None);
+        let config = RateLimitConfig::new(
+            Some("120/60".into()),
+            Some("diff=20/60, bad, qrcode=x/1".into()),
+            Some("10.0.0.0/8, ::1".into()),
+        );
+        assert_eq!(config.budget("diff"), Budget::parse("20/60"));
+        assert_eq!(config.budget("qrcode"), Budget::parse("120/60"));
+        assert!(config.is_allowlisted("10.1.2.3"));
+        assert!(config.is_allowlisted("::1"));
+        assert!(!config.is_allowlisted("192.168.0.1"));
+        assert_eq!(route("/api/qrcode/download"), "qrcode");
+        assert_eq!(route("/api/live/diff"), "diff");
+    }
+}
@@ -0,0 +1,259 @@
+use crate::archive;
+use crate::error::ToolError;
+use crate::models::*;
+use crate::{system, utils};
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+// One service description that drives every generator in the bundle, so names and ports
+// line up across the Dockerfile, compose file, k8s manifests, nginx and Ansible.
+#[derive(Deserialize, JsonSchema)]
+pub struct ScaffoldRequest {
+    // DNS label: used as the folder, container, k8s object and compose service name
+    pub name: String,
+    #[serde(default = "default_image")]
+    pub image: String,
+    // Port the service listens on inside the container
+    #[serde(default = "default_port")]
+    pub port: u16,
+    #[serde(default = "default_replicas")]
+    pub replicas: i32,
+    #[serde(default = "default_namespace")]
+    pub namespace: String,
+    #[serde(default)]
+    pub env: Vec<K8sEnvVar>,
+    // Public host name; the k8s Ingress is only generated when it is set
+    #[serde(default)]
+    pub domain: String,
+    // Dockerfile CMD, e.g. `["./server"]`
+    #[serde(default)]
+    pub cmd: String,
+    #[serde(default = "default_archive")]
+    #[schemars(extend("enum" = ["zip", "tar"]))]
+    pub archive: String,
+}
+
+fn default_image() -> String {
+    "nginx:latest".to_string()
+}
+fn default_port() -> u16 {
+    8080
+}
+fn default_replicas() -> i32 {
+    1
+}
+fn default_namespace() -> String {
+    "default".to_string()
+}
+fn default_archive() -> String {
+    "zip".to_string()
+}
+
+pub struct Bundle {
+    pub filename: String,
+    pub media_type: &'static str,
+    pub bytes: Vec<u8>,
+}
+
+pub fn build(data: &ScaffoldRequest) -> Result<Bundle, ToolError> {
+    let files = files(data)?;
+    match data.archive.as_str() {
+        "zip" => Ok(Bundle {
+            filename: format!("{}.zip", data.name),
+            media_type: "application/zip",
+            bytes: archive::zip(&files),
+        }),
+        "tar" => Ok(Bundle {
+            filename: format!("{}.tar", data.name),
+            media_type: "application/x-tar",
+            bytes: archive::tar(&files),
+        }),
+        other => Err(ToolError::invalid("archive", "scaffold.archive", &[&other])),
+    }
+}
+
+// Paths are relative to the archive root and all live under `<name>/`.
+pub fn files(data: &ScaffoldRequest) -> Result<Vec<(String, String)>, ToolError> {
+    let name = data.name.as_str();
+    if !is_dns_label(name) {
+        return Err(ToolError::invalid("name", "scaffold.name", &[]));
+    }
+    if data.port == 0 {
+        return Err(ToolError::invalid("port", "scaffold.port", &[]));
+    }
+    let port = data.port as i32;
+    let env: Vec<K8sEnvVar> = data
+        .env
+        .iter()
+        .filter(|e| !e.key.is_empty())
+        .cloned()
+        .collect();
+    // Env is interpolated into the Dockerfile, k8s manifests and Ansible task, so keys must
+    // be plain identifiers and values must not break out of a double-quoted string.
+    for e in &env {
+        if !is_env_name(&e.key) {
+            return Err(ToolError::invalid("env", "scaffold.env_key", &[&e.key]));
+        }
+        if e.value.contains(['"', '\\', '\n', '\r']) {
+            return Err(ToolError::invalid("env", "scaffold.env_value", &[&e.key]));
+        }
+    }
+
+    let dockerfile = system::generate_dockerfile(&[DockerfileStage {
+        image: data.image.clone(),
+        workdir: "/app".to_string(),
+        copy: ". .".to_string(),
+        env: env
+            .iter()
+            .map(|e| format!("{}=\"{}\"", e.key, e.value))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        expose: data.port.to_string(),
+        cmd: data.cmd.clone(),
+        ..Default::default()
+    }]);
+
+    let compose = system::generate_compose(&ComposeRequest {
+        name: name.to_string(),
+        image: data.image.clone(),
+        build: true,
+        port,
+        host_port: 0,
+        env: env.clone(),
+        restart: "unless-stopped".to_string(),
+    });
+
+    let k8s = |kind: &str, service_port: i32| {
+        system::generate_k8s_yaml(&K8sRequest {
+            kind: kind.to_string(),
+            name: name.to_string(),
+            namespace: data.namespace.clone(),
+            image: data.image.clone(),
+            replicas: data.replicas,
+            port: service_port,
+            target_port: port,
+            ingress_host: data.domain.clone(),
+            env: env.clone(),
+            ..Default::default()
+        })
+    };
+
+    let nginx = utils::generate_nginx_config(
+        &data.domain,
+        80,
+        "",
+        &[NginxLocation {
+            path: "/".to_string(),
+            proxy: format!("http://127.0.0.1:{}", port),
+            root: String::new(),
+            spa: false,
+        }],
+        "",
+        false,
+        false,
+        "",
+        "",
+        true,
+        "",
+        "",
+        "",
+        "",
+        "",
+    );
+
+    let mut task = format!(
+        "- name: Run {name} container\n  community.docker.docker_container:\n    name: {name}\n    image: {image}\n    restart_policy: unless-stopped\n    published_ports:\n      - \"{port}:{port}\"\n",
+        name = name,
+        image = data.image,
+        port = port
+    );
+    if !env.is_empty() {
+        task.push_str("    env:\n");
+        for e in &env {
+            task.push_str(&format!("      {}: \"{}\"\n", e.key, e.value));
+        }
+    }
+    let playbook = system::generate_ansible_yaml(&AnsibleRequest {
+        play_name: format!("Deploy {}", name),
+        r#become: true,
+        tasks: task,
+        ..Default::default()
+    });
+
+    let mut files = vec![
+        (format!("{}/Dockerfile", name), dockerfile),
+        (format!("{}/docker-compose.yml", name), compose),
+        (
+            format!("{}/k8s/deployment.yaml", name),
+            k8s("Deployment", port)?,
+        ),
+        (format!("{}/k8s/service.yaml", name), k8s("Service", 80)?),
+    ];
+    if !data.domain.is_empty() {
+        files.push((format!("{}/k8s/ingress.yaml", name), k8s("Ingress", 80)?));
+    }
+    files.push((format!("{}/nginx.conf", name), nginx));
+    files.push((format!("{}/ansible/deploy.yml", name), playbook));
+    Ok(files)
+}
+
+fn is_env_name(key: &str) -> bool {
+    let mut chars = key.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn is_dns_label(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 63
+        && !name.starts_with('-')
+        && !name.ends_with('-')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(env: &[(&str, &str)]) -> ScaffoldRequest {
+        serde_json::from_value(serde_json::json!({
+            "name": "api",
+            "env": env
+                .iter()
+                .map(|(key, value)| serde_json::json!({ "key": key, "value": value }))
+                .collect::<Vec<_>>(),
+        }))
+        .unwrap()
+    }
+
+    fn file<'a>(files: &'a [(String, String)], path: &str) -> &'a str {
+        &files.iter().find(|(name, _)| name == path).unwrap().1
+    }
+
+    #[test]
+    fn env_is_written_to_every_file() {
+        let files = files(&request(&[("LOG_LEVEL", "debug info")])).unwrap();
+        assert!(file(&files, "api/Dockerfile").contains("ENV LOG_LEVEL=\"debug info\"\n"));
+        assert!(
+            file(&files, "api/docker-compose.yml").contains("      LOG_LEVEL: \"debug info\"\n")
+        );
+        assert!(file(&files, "api/ansible/deploy.yml").contains("LOG_LEVEL: \"debug info\"\n"));
+    }
+
+    #[test]
+    fn env_that_could_inject_is_rejected() {
+        for env in [
+            ("A", "b\"\n    privileged: true"),
+            ("A", "b\\"),
+            ("A", "b\rc"),
+            ("1A", "b"),
+            ("A-B", "b"),
+            ("A: b\n  c", "d"),
+        ] {
+            let err = files(&request(&[env])).err().unwrap();
+            assert_eq!(err.status(), 422, "{:?}", env);
+        }
+    }
+}
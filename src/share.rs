@@ -0,0 +1,161 @@
+use crate::error::ToolError;
+use crate::tools::Context;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+// Lifetime of a share link in seconds: a week unless asked otherwise, at most 30 days. KV
+// does not take expirations under a minute.
+const DEFAULT_TTL: u64 = 7 * 86400;
+const MAX_TTL: u64 = 30 * 86400;
+const MIN_TTL: u64 = 60;
+
+const ID_LEN: usize = 10;
+const ID_ALPHABET: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ShareRequest {
+    // Tool name as in `/api/<tool>`
+    pub tool: String,
+    // Request fields of the tool, stored as given
+    #[serde(default)]
+    pub input: Map<String, Value>,
+    // Seconds until the link expires, 60 to 2592000; defaults to a week
+    pub ttl: Option<u64>,
+}
+
+// What a share ID resolves to; also the value stored in KV.
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct Shared {
+    pub tool: String,
+    pub input: Map<String, Value>,
+    // Unix seconds
+    pub expires_at: u64,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct ShareResponse {
+    pub id: String,
+    // Opens the tool on the homepage with the saved input
+    pub url: String,
+    // Unix seconds
+    pub expires_at: u64,
+}
+
+impl ShareRequest {
+    // Only tools the caller could call can be shared. The input itself is not validated, so a
+    // failing request can be shared too. Returns the entry and its TTL in seconds.
+    pub fn into_shared(self, ctx: &Context, now: u64) -> Result<(Shared, u64), ToolError> {
+        let tool = ctx
+            .tools
+            .find(&self.tool)
+            .ok_or_else(|| ToolError::unknown_tool(&self.tool))?;
+        ctx.access.check(tool.group())?;
+        let ttl = self.ttl.unwrap_or(DEFAULT_TTL).clamp(MIN_TTL, MAX_TTL);
+        let shared = Shared {
+            tool: self.tool,
+            input: self.input,
+            expires_at: now + ttl,
+        };
+        Ok((shared, ttl))
+    }
+}
+
+impl ShareResponse {
+    pub fn new(id: String, shared: &Shared) -> Self {
+        ShareResponse {
+            url: format!("/s/{}", id),
+            id,
+            expires_at: shared.expires_at,
+        }
+    }
+}
+
+// Ten base62 characters from a v4 UUID, about 59 random bits.
+pub fn new_id() -> String {
+    let mut n = uuid::Uuid::new_v4().as_u128();
+    (0..ID_LEN)
+        .map(|_| {
+            let c = ID_ALPHABET[(n % 62) as usize] as char;
+            n /= 62;
+            c
+        })
+        .collect()
+}
+
+// Rejects anything `new_id` cannot have produced before it reaches the store.
+pub fn is_valid_id(id: &str) -> bool {
+    id.len() == ID_LEN && id.bytes().all(|b| b.is_ascii_alphanumeric())
+}
+
+// Stand-in for the SHARES KV namespace: serialized entries with their expiry, dropped once
+// read after it.
+#[derive(Default)]
+pub struct MemoryStore {
+    entries: HashMap<String, (String, u64)>,
+}
+
+impl MemoryStore {
+    pub fn put(&mut self, id: &str, value: String, expires_at: u64) {
+        self.entries.insert(id.to_string(), (value, expires_at));
+    }
+
+    pub fn get(&mut self, id: &str, now: u64) -> Option<String> {
+        match self.entries.get(id) {
+            Some((_, expires_at)) if *expires_at <= now => {
+                self.entries.remove(id);
+                None
+            }
+            entry => entry.map(|(value, _)| value.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_store_round_trips_until_expiry() {
+        let mut store = MemoryStore::default();
+        store.put("abc", "{\"tool\":\"md5\"}".into(), 100);
+        assert_eq!(store.get("abc", 99).as_deref(), Some("{\"tool\":\"md5\"}"));
+        // Reading does not consume the entry
+        assert!(store.get("abc", 99).is_some());
+        assert_eq!(store.get("missing", 0), None);
+        assert_eq!(store.get("abc", 100), None);
+        assert_eq!(store.get("abc", 0), None, "expired entries are dropped");
+    }
+
+    #[test]
+    fn new_ids_are_valid() {
+        for _ in 0..100 {
+            assert!(is_valid_id(&new_id()));
+        }
+        assert!(!is_valid_id("short"));
+        assert!(!is_valid_id("../../etc/"));
+    }
+
+    #[cfg(feature = "crypto")]
+    #[test]
+    fn ttl_is_clamped() {
+        let ctx = Context::default();
+        let request = |ttl| ShareRequest {
+            tool: "md5".into(),
+            input: Map::new(),
+            ttl,
+        };
+        let ttl = |r: ShareRequest| r.into_shared(&ctx, 1000).unwrap().1;
+        assert_eq!(ttl(request(None)), DEFAULT_TTL);
+        assert_eq!(ttl(request(Some(1))), MIN_TTL);
+        assert_eq!(ttl(request(Some(u64::MAX))), MAX_TTL);
+        let (shared, _) = request(Some(3600)).into_shared(&ctx, 1000).unwrap();
+        assert_eq!(shared.expires_at, 4600);
+        let unknown = ShareRequest {
+            tool: "no-such-tool".into(),
+            ..request(None)
+        };
+        assert!(unknown.into_shared(&ctx, 1000).is_err());
+    }
+}
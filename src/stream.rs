@@ -0,0 +1,146 @@
+use serde_json::Value;
+
+// Rows written per chunk of the response body: few enough to keep memory flat, enough to
+// keep the per-chunk overhead of crossing into JavaScript small.
+const CHUNK_ROWS: usize = 500;
+
+// Output of a generator one row at a time. Every row is a JSON object with the same keys.
+pub struct Rows {
+    pub count: usize,
+    pub items: Box<dyn Iterator<Item = Value>>,
+    // Seed a random generator was given, sent back in the X-Seed header
+    pub seed: Option<u64>,
+}
+
+impl Rows {
+    pub fn new(count: usize, items: impl Iterator<Item = Value> + 'static) -> Self {
+        Rows {
+            count,
+            items: Box::new(items.take(count)),
+            seed: None,
+        }
+    }
+
+    pub fn seeded(self, seed: Option<u64>) -> Self {
+        Rows { seed, ..self }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RowFormat {
+    Ndjson,
+    Csv,
+}
+
+impl RowFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            RowFormat::Ndjson => "application/x-ndjson; charset=utf-8",
+            RowFormat::Csv => "text/csv; charset=utf-8",
+        }
+    }
+
+    // `?format=csv|ndjson` wins over the Accept header; NDJSON unless CSV is asked for.
+    pub fn negotiate(param: Option<&str>, accept: &str) -> RowFormat {
+        match param {
+            Some("csv") => return RowFormat::Csv,
+            Some("ndjson") | Some("jsonl") => return RowFormat::Ndjson,
+            _ => {}
+        }
+        let csv = accept
+            .split(',')
+            .any(|media| media.split(';').next().unwrap_or("").trim() == "text/csv");
+        if csv {
+            RowFormat::Csv
+        } else {
+            RowFormat::Ndjson
+        }
+    }
+}
+
+// The response body as text chunks, generated as they are pulled. CSV starts with a header
+// line taken from the keys of the first row.
+pub struct Chunks {
+    rows: Rows,
+    format: RowFormat,
+    header: bool,
+}
+
+pub fn chunks(rows: Rows, format: RowFormat) -> Chunks {
+    Chunks {
+        rows,
+        format,
+        header: format == RowFormat::Csv,
+    }
+}
+
+impl Iterator for Chunks {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        let mut chunk = String::new();
+        for row in self.rows.items.by_ref().take(CHUNK_ROWS) {
+            match self.format {
+                RowFormat::Ndjson => chunk.push_str(&row.to_string()),
+                RowFormat::Csv => {
+                    let fields = row.as_object().cloned().unwrap_or_default();
+                    if self.header {
+                        self.header = false;
+                        let names: Vec<String> = fields.keys().map(|k| csv_field(k)).collect();
+                        chunk.push_str(&names.join(","));
+                        chunk.push('\n');
+                    }
+                    let values: Vec<String> = fields.values().map(csv_value).collect();
+                    chunk.push_str(&values.join(","));
+                }
+            }
+            chunk.push('\n');
+        }
+        if chunk.is_empty() {
+            None
+        } else {
+            Some(chunk)
+        }
+    }
+}
+
+fn csv_value(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => csv_field(s),
+        other => csv_field(&other.to_string()),
+    }
+}
+
+// Quotes a field as RFC 4180 asks when it holds a separator, quote or line break.
+fn csv_field(s: &str) -> String {
+    if !s.contains([',', '"', '\n', '\r']) {
+        return s.to_string();
+    }
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        if c == '"' {
+            quoted.push('"');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+// `count` rows of `{"<name>": value}`, for generators whose rows are single strings.
+pub fn column(
+    name: &'static str,
+    count: usize,
+    values: impl Iterator<Item = String> + 'static,
+) -> Rows {
+    Rows::new(
+        count,
+        values.map(move |v| {
+            let mut row = serde_json::Map::new();
+            row.insert(name.to_string(), Value::String(v));
+            Value::Object(row)
+        }),
+    )
+}
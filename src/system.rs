@@ -1,23 +1,29 @@
+// The command builders take one argument per field of their request
+#![allow(clippy::too_many_arguments)]
+
+#[cfg(feature = "devops")]
+use crate::error::ToolError;
+#[cfg(feature = "devops")]
+use crate::i18n::{tr, Locale};
+#[cfg(any(feature = "devops", feature = "network"))]
 use crate::models::*;
 
-pub fn calculate_chmod(octal: &str, file: &str) -> ChmodResponse {
+#[cfg(feature = "devops")]
+pub fn calculate_chmod(octal: &str, file: &str) -> Result<ChmodResponse, ToolError> {
     if octal.len() != 3 || octal.chars().any(|c| !c.is_digit(8)) {
-        return ChmodResponse {
-            valid: false,
-            command: "Invalid".into(),
-        };
+        return Err(ToolError::invalid("octal", "chmod.invalid", &[&octal]));
     }
     let f = if file.trim().is_empty() {
         "filename"
     } else {
         file.trim()
     };
-    ChmodResponse {
-        valid: true,
+    Ok(ChmodResponse {
         command: format!("chmod {} {}", octal, f),
-    }
+    })
 }
 
+#[cfg(feature = "devops")]
 pub fn generate_tar(
     op: &str,
     comp: &str,
@@ -71,6 +77,7 @@ pub fn generate_tar(
     TarResponse { command: cmd }
 }
 
+#[cfg(feature = "devops")]
 pub fn generate_ps(
     format: &str,
     sort: &str,
@@ -93,7 +100,7 @@ pub fn generate_ps(
                 cmd.push_str("ww");
             }
             if threads {
-                cmd.push_str("L");
+                cmd.push('L');
             }
         }
         _ => {
@@ -134,6 +141,7 @@ pub fn generate_ps(
     PsResponse { command: cmd }
 }
 
+#[cfg(feature = "devops")]
 pub fn generate_strace(
     target: &str,
     is_pid: bool,
@@ -181,6 +189,7 @@ pub fn generate_strace(
     StraceResponse { command: cmd }
 }
 
+#[cfg(feature = "devops")]
 pub fn generate_iostat(
     interval: &str,
     count: &str,
@@ -230,6 +239,7 @@ pub fn generate_iostat(
     IostatResponse { command: cmd }
 }
 
+#[cfg(feature = "devops")]
 pub fn generate_nice(
     mode: &str,
     priority: i32,
@@ -238,7 +248,7 @@ pub fn generate_nice(
     target: &str,
 ) -> NiceResponse {
     let mut cmd = String::new();
-    let prio = priority.max(-20).min(19);
+    let prio = priority.clamp(-20, 19);
 
     if mode == "renice" {
         cmd.push_str("renice -n ");
@@ -267,6 +277,7 @@ pub fn generate_nice(
     NiceResponse { command: cmd }
 }
 
+#[cfg(feature = "devops")]
 pub fn generate_ls(
     path: &str,
     all: bool,
@@ -322,6 +333,7 @@ pub fn generate_ls(
     LsResponse { command: cmd }
 }
 
+#[cfg(feature = "devops")]
 pub fn generate_systemctl(
     operation: &str,
     service: &str,
@@ -358,6 +370,7 @@ pub fn generate_systemctl(
     SystemctlResponse { command: cmd }
 }
 
+#[cfg(feature = "devops")]
 pub fn generate_find(
     path: &str,
     name: &str,
@@ -417,6 +430,7 @@ pub fn generate_find(
     FindResponse { command: cmd }
 }
 
+#[cfg(feature = "devops")]
 pub fn generate_dockerfile(stages: &[DockerfileStage]) -> String {
     let mut df = String::new();
 
@@ -505,6 +519,7 @@ pub fn generate_dockerfile(stages: &[DockerfileStage]) -> String {
     df
 }
 
+#[cfg(feature = "devops")]
 pub fn generate_rsync(
     source: &str,
     user: &str,
@@ -604,6 +619,7 @@ pub fn generate_rsync(
     }
 }
 
+#[cfg(feature = "devops")]
 pub fn generate_awk(separator: &str, variable: &str, code: &str, file: &str) -> AwkResponse {
     let mut cmd = String::from("awk");
 
@@ -635,6 +651,7 @@ pub fn generate_awk(separator: &str, variable: &str, code: &str, file: &str) ->
     AwkResponse { command: cmd }
 }
 
+#[cfg(feature = "devops")]
 pub fn generate_sed(
     operation: &str,
     pattern: &str,
@@ -681,6 +698,7 @@ pub fn generate_sed(
     SedResponse { command: cmd }
 }
 
+#[cfg(feature = "devops")]
 pub fn generate_tcpdump(
     interface: &str,
     protocol: &str,
@@ -729,6 +747,7 @@ pub fn generate_tcpdump(
     TcpdumpResponse { command: cmd }
 }
 
+#[cfg(feature = "devops")]
 pub fn generate_git(
     cmd: &str,
     target: &str,
@@ -747,11 +766,9 @@ pub fn generate_git(
 ) -> GitResponse {
     let mut command = format!("git {}", cmd);
     match cmd {
-        "init" | "clone" => {
-            if !target.trim().is_empty() {
-                command.push(' ');
-                command.push_str(target.trim());
-            }
+        "init" | "clone" if !target.trim().is_empty() => {
+            command.push(' ');
+            command.push_str(target.trim());
         }
         "add" => {
             if opt_all {
@@ -812,11 +829,9 @@ pub fn generate_git(
                 command.push_str(target.trim());
             }
         }
-        "merge" => {
-            if !target.trim().is_empty() {
-                command.push(' ');
-                command.push_str(target.trim());
-            }
+        "merge" if !target.trim().is_empty() => {
+            command.push(' ');
+            command.push_str(target.trim());
         }
         "log" => {
             if opt_oneline {
@@ -835,14 +850,12 @@ pub fn generate_git(
                 command.push_str(target.trim());
             }
         }
-        "remote" => {
-            if !remote.trim().is_empty() {
-                command.push_str(" add ");
-                command.push_str(remote.trim());
-                if !target.trim().is_empty() {
-                    command.push(' ');
-                    command.push_str(target.trim());
-                }
+        "remote" if !remote.trim().is_empty() => {
+            command.push_str(" add ");
+            command.push_str(remote.trim());
+            if !target.trim().is_empty() {
+                command.push(' ');
+                command.push_str(target.trim());
             }
         }
         _ => {}
@@ -850,19 +863,26 @@ pub fn generate_git(
     GitResponse { command }
 }
 
-pub fn generate_git_cmd(action: &str, tag: &str, msg: &str, branch: &str) -> GitCmdResponse {
+#[cfg(feature = "devops")]
+pub fn generate_git_cmd(
+    action: &str,
+    tag: &str,
+    msg: &str,
+    branch: &str,
+    locale: Locale,
+) -> GitCmdResponse {
     let (command, description) = match action {
         "undo_commit" => (
             "git reset --soft HEAD~1".to_string(),
-            "撤销最近一次提交，但保留文件修改（Soft Reset）".to_string(),
+            tr(locale, "git.undo_commit", &[]),
         ),
         "undo_changes" => (
             "git checkout .".to_string(),
-            "撤销工作区所有修改（危险：会丢失未提交的改动）".to_string(),
+            tr(locale, "git.undo_changes", &[]),
         ),
         "log_graph" => (
             "git log --graph --oneline --decorate --all".to_string(),
-            "以图形化方式查看提交历史".to_string(),
+            tr(locale, "git.log_graph", &[]),
         ),
         "tag" => {
             let t = if tag.trim().is_empty() {
@@ -877,7 +897,7 @@ pub fn generate_git_cmd(action: &str, tag: &str, msg: &str, branch: &str) -> Git
             };
             (
                 format!("git tag -a {} -m \"{}\" && git push origin {}", t, m, t),
-                "创建并推送带注释的标签".to_string(), // Tag and push with annotation
+                tr(locale, "git.tag", &[]),
             )
         }
         "branch_delete" => {
@@ -888,12 +908,12 @@ pub fn generate_git_cmd(action: &str, tag: &str, msg: &str, branch: &str) -> Git
             };
             (
                 format!("git branch -d {} && git push origin --delete {}", b, b),
-                "删除本地和远程分支".to_string(), // Delete local and remote branch
+                tr(locale, "git.branch_delete", &[]),
             )
         }
         "stash" => (
             "git stash && git pull && git stash pop".to_string(),
-            "暂存修改，拉取代码，然后恢复修改".to_string(),
+            tr(locale, "git.stash", &[]),
         ),
         _ => ("git help".to_string(), "".to_string()),
     };
@@ -903,6 +923,7 @@ pub fn generate_git_cmd(action: &str, tag: &str, msg: &str, branch: &str) -> Git
     }
 }
 
+#[cfg(feature = "devops")]
 pub fn generate_firewall(
     op: &str,
     zone: &str,
@@ -946,6 +967,7 @@ pub fn generate_firewall(
     FirewallResponse { command: cmd }
 }
 
+#[cfg(feature = "network")]
 pub fn generate_curl(method: &str, url: &str, headers: &str, body: &str) -> CurlResponse {
     let m = if method.trim().is_empty() {
         "GET".to_string()
@@ -983,20 +1005,18 @@ pub fn generate_curl(method: &str, url: &str, headers: &str, body: &str) -> Curl
     }
 
     let mut has_payload = false;
-    if ["POST", "PUT", "PATCH"].contains(&m.as_str()) {
-        if !body.is_empty() {
-            has_payload = true;
-            let py_body = body.replace('\\', "\\\\").replace('"', "\\\"");
-            py.push_str(&format!("\npayload = \"{}\"\n", py_body));
-
-            // Check if body is valid JSON to add content-type header if not present
-            if serde_json::from_str::<serde_json::Value>(body).is_ok() {
-                cmd.push_str(" \\\n  -H 'Content-Type: application/json'");
-            }
-            cmd.push_str(" \\\n  -d '");
-            cmd.push_str(&body.replace('\'', "'\\''"));
-            cmd.push('\'');
+    if ["POST", "PUT", "PATCH"].contains(&m.as_str()) && !body.is_empty() {
+        has_payload = true;
+        let py_body = body.replace('\\', "\\\\").replace('"', "\\\"");
+        py.push_str(&format!("\npayload = \"{}\"\n", py_body));
+
+        // Check if body is valid JSON to add content-type header if not present
+        if serde_json::from_str::<serde_json::Value>(body).is_ok() {
+            cmd.push_str(" \\\n  -H 'Content-Type: application/json'");
         }
+        cmd.push_str(" \\\n  -d '");
+        cmd.push_str(&body.replace('\'', "'\\''"));
+        cmd.push('\'');
     }
 
     py.push_str(&format!("\nresponse = requests.request(\"{}\", url", m));
@@ -1013,3 +1033,383 @@ pub fn generate_curl(method: &str, url: &str, headers: &str, body: &str) -> Curl
         python: py,
     }
 }
+
+#[cfg(feature = "devops")]
+pub fn generate_k8s_yaml(data: &K8sRequest) -> Result<String, ToolError> {
+    let mut yaml = String::new();
+
+    if data.kind == "Deployment" {
+        yaml.push_str(&format!(
+            r#"apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: {}
+  namespace: {}
+  labels:
+    app: {}
+spec:
+  replicas: {}
+  selector:
+    matchLabels:
+      app: {}
+  template:
+    metadata:
+      labels:
+        app: {}
+    spec:
+      containers:
+      - name: {}
+        image: {}
+        imagePullPolicy: {}
+        ports:
+        - containerPort: {}
+"#,
+            data.name,
+            data.namespace,
+            data.name,
+            data.replicas,
+            data.name,
+            data.name,
+            data.name,
+            data.image,
+            data.pull_policy,
+            data.port
+        ));
+
+        if data.cpu_limit.is_some()
+            || data.memory_limit.is_some()
+            || data.cpu_request.is_some()
+            || data.memory_request.is_some()
+        {
+            yaml.push_str("        resources:\n");
+            if data.cpu_limit.is_some() || data.memory_limit.is_some() {
+                yaml.push_str("          limits:\n");
+                if let Some(ref cpu) = data.cpu_limit {
+                    yaml.push_str(&format!("            cpu: {}\n", cpu));
+                }
+                if let Some(ref mem) = data.memory_limit {
+                    yaml.push_str(&format!("            memory: {}\n", mem));
+                }
+            }
+            if data.cpu_request.is_some() || data.memory_request.is_some() {
+                yaml.push_str("          requests:\n");
+                if let Some(ref cpu) = data.cpu_request {
+                    yaml.push_str(&format!("            cpu: {}\n", cpu));
+                }
+                if let Some(ref mem) = data.memory_request {
+                    yaml.push_str(&format!("            memory: {}\n", mem));
+                }
+            }
+        }
+
+        if !data.env.is_empty() {
+            yaml.push_str("        env:\n");
+            for e in &data.env {
+                if !e.key.is_empty() && !e.value.is_empty() {
+                    yaml.push_str(&format!(
+                        "        - name: {}\n          value: \"{}\"\n",
+                        e.key, e.value
+                    ));
+                }
+            }
+        }
+
+        yaml.push_str(&format!("      restartPolicy: {}", data.restart_policy));
+    } else if data.kind == "Service" {
+        yaml.push_str(&format!(
+            r#"apiVersion: v1
+kind: Service
+metadata:
+  name: {}
+  namespace: {}
+  labels:
+    app: {}
+spec:
+  type: {}
+  selector:
+    app: {}
+  ports:
+  - protocol: TCP
+    port: {}
+    targetPort: {}
+"#,
+            data.name,
+            data.namespace,
+            data.name,
+            data.service_type,
+            data.name,
+            data.port,
+            data.target_port
+        ));
+    } else if data.kind == "Ingress" {
+        yaml.push_str(&format!(
+            r#"apiVersion: networking.k8s.io/v1
+kind: Ingress
+metadata:
+  name: {}
+  namespace: {}
+  annotations:
+    nginx.ingress.kubernetes.io/rewrite-target: /
+spec:
+  rules:
+  - host: {}
+    http:
+      paths:
+      - path: {}
+        pathType: Prefix
+        backend:
+          service:
+            name: {}
+            port:
+              number: {}
+"#,
+            data.name, data.namespace, data.ingress_host, data.ingress_path, data.name, data.port
+        ));
+    } else if data.kind == "CronJob" {
+        yaml.push_str(&format!(
+            r#"apiVersion: batch/v1
+kind: CronJob
+metadata:
+  name: {}
+  namespace: {}
+spec:
+  schedule: "{}"
+  jobTemplate:
+    spec:
+      template:
+        spec:
+          containers:
+          - name: {}
+            image: {}
+            imagePullPolicy: {}
+            command:
+            - /bin/sh
+            - -c
+            - "echo Hello Kubernetes"
+          restartPolicy: OnFailure
+"#,
+            data.name, data.namespace, data.schedule, data.name, data.image, data.pull_policy
+        ));
+    } else if data.kind == "ConfigMap" {
+        yaml.push_str(&format!(
+            r#"apiVersion: v1
+kind: ConfigMap
+metadata:
+  name: {}
+  namespace: {}
+data:
+"#,
+            data.name, data.namespace
+        ));
+        if !data.env.is_empty() {
+            for e in &data.env {
+                if e.key.is_empty() || e.value.is_empty() {
+                    continue;
+                }
+                if e.value.contains('\n') {
+                    // Whole files (e.g. piped YAML) go in as literal blocks
+                    yaml.push_str(&format!("  {}: |\n", e.key));
+                    for line in e.value.lines() {
+                        yaml.push_str(&format!("    {}\n", line));
+                    }
+                } else {
+                    yaml.push_str(&format!("  {}: \"{}\"\n", e.key, e.value));
+                }
+            }
+        } else {
+            yaml.push_str("  config.json: |\n    {\n      \"key\": \"value\"\n    }");
+        }
+    } else if data.kind == "Secret" {
+        yaml.push_str(&format!(
+            r#"apiVersion: v1
+kind: Secret
+metadata:
+  name: {}
+  namespace: {}
+type: Opaque
+data:
+  # Data should be base64 encoded
+"#,
+            data.name, data.namespace
+        ));
+        if !data.env.is_empty() {
+            use base64::{engine::general_purpose, Engine as _};
+            for e in &data.env {
+                if !e.key.is_empty() && !e.value.is_empty() {
+                    let b64 = general_purpose::STANDARD.encode(&e.value);
+                    yaml.push_str(&format!("  {}: {}\n", e.key, b64));
+                }
+            }
+        } else {
+            yaml.push_str("  username: YWRtaW4=");
+        }
+    } else {
+        return Err(ToolError::invalid("kind", "k8s.kind", &[&data.kind]));
+    }
+
+    Ok(yaml)
+}
+
+#[cfg(feature = "devops")]
+pub fn generate_k8s_cmd(data: &K8sCmdRequest, locale: Locale) -> (String, String) {
+    let ns = if data.namespace.is_empty() {
+        "default"
+    } else {
+        &data.namespace
+    };
+    let name = if data.resource_name.is_empty() {
+        "app"
+    } else {
+        &data.resource_name
+    };
+    let rtype = if data.resource_type.is_empty() {
+        "pod"
+    } else {
+        &data.resource_type
+    };
+
+    let output = if data.output_format.is_empty() {
+        String::new()
+    } else {
+        format!(" -o {}", data.output_format)
+    };
+
+    match data.action.as_str() {
+        "get" => (
+            format!("kubectl get {} -n {}{}", rtype, ns, output),
+            tr(locale, "k8s.get", &[&rtype]),
+        ),
+        "describe" => (
+            format!("kubectl describe {} {} -n {}", rtype, name, ns),
+            tr(locale, "k8s.describe", &[&rtype, &name]),
+        ),
+        "delete" => (
+            format!("kubectl delete {} {} -n {}", rtype, name, ns),
+            tr(locale, "k8s.delete", &[&rtype, &name]),
+        ),
+        "logs" => {
+            let target = if rtype == "pod" {
+                name.to_string()
+            } else {
+                format!("{}/{}", rtype, name)
+            };
+            (
+                format!("kubectl logs -f {} -n {}", target, ns),
+                tr(locale, "k8s.logs", &[&rtype, &name]),
+            )
+        }
+        "exec" => (
+            format!("kubectl exec -it {} -n {} -- /bin/sh", name, ns),
+            tr(locale, "k8s.exec", &[&name]),
+        ),
+        "scale" => (
+            format!(
+                "kubectl scale {} {} --replicas={} -n {}",
+                rtype, name, data.replicas, ns
+            ),
+            tr(locale, "k8s.scale", &[&rtype, &name, &data.replicas]),
+        ),
+        "port_forward" => (
+            format!(
+                "kubectl port-forward {} {}:{} -n {}",
+                if rtype == "pod" {
+                    name.to_string()
+                } else {
+                    format!("{}/{}", rtype, name)
+                },
+                data.local_port,
+                data.remote_port,
+                ns
+            ),
+            tr(
+                locale,
+                "k8s.port_forward",
+                &[&rtype, &data.local_port, &data.remote_port],
+            ),
+        ),
+        "rollout_restart" => (
+            format!("kubectl rollout restart {} {} -n {}", rtype, name, ns),
+            tr(locale, "k8s.rollout_restart", &[&rtype, &name]),
+        ),
+        "rollout_status" => (
+            format!("kubectl rollout status {} {} -n {}", rtype, name, ns),
+            tr(locale, "k8s.rollout_status", &[&rtype, &name]),
+        ),
+        "rollout_history" => (
+            format!("kubectl rollout history {} {} -n {}", rtype, name, ns),
+            tr(locale, "k8s.rollout_history", &[&rtype, &name]),
+        ),
+        "rollout_undo" => (
+            format!("kubectl rollout undo {} {} -n {}", rtype, name, ns),
+            tr(locale, "k8s.rollout_undo", &[&rtype, &name]),
+        ),
+        _ => ("kubectl --help".to_string(), tr(locale, "k8s.help", &[])),
+    }
+}
+
+#[cfg(feature = "devops")]
+pub fn generate_ansible_yaml(data: &AnsibleRequest) -> String {
+    let mut yaml = String::new();
+    yaml.push_str(&format!("- name: {}\n", data.play_name));
+    yaml.push_str(&format!("  hosts: {}\n", data.hosts));
+    if data.r#become {
+        yaml.push_str("  become: yes\n");
+    }
+    if !data.gather_facts {
+        yaml.push_str("  gather_facts: no\n");
+    }
+    if !data.vars.trim().is_empty() {
+        yaml.push_str("  vars:\n");
+        for line in data.vars.lines() {
+            yaml.push_str(&format!("    {}\n", line));
+        }
+    }
+    yaml.push_str("  tasks:\n");
+
+    if data.tasks.trim().is_empty() {
+        yaml.push_str("    - name: Ping hosts\n      ping:\n");
+    } else {
+        for line in data.tasks.lines() {
+            yaml.push_str(&format!("    {}\n", line));
+        }
+    }
+
+    if !data.handlers.trim().is_empty() {
+        yaml.push_str("  handlers:\n");
+        for line in data.handlers.lines() {
+            yaml.push_str(&format!("    {}\n", line));
+        }
+    }
+    yaml
+}
+
+#[cfg(feature = "devops")]
+pub fn generate_compose(data: &ComposeRequest) -> String {
+    let mut yaml = String::from("services:\n");
+    yaml.push_str(&format!("  {}:\n", data.name));
+    if data.build {
+        yaml.push_str("    build: .\n");
+    }
+    yaml.push_str(&format!("    image: {}\n", data.image));
+    yaml.push_str(&format!("    container_name: {}\n", data.name));
+    yaml.push_str(&format!("    restart: {}\n", data.restart));
+    if data.port > 0 {
+        let host_port = if data.host_port > 0 {
+            data.host_port
+        } else {
+            data.port
+        };
+        yaml.push_str("    ports:\n");
+        yaml.push_str(&format!("      - \"{}:{}\"\n", host_port, data.port));
+    }
+    let env: Vec<&K8sEnvVar> = data.env.iter().filter(|e| !e.key.is_empty()).collect();
+    if !env.is_empty() {
+        yaml.push_str("    environment:\n");
+        for e in env {
+            // A JSON string is a valid double-quoted YAML scalar, escapes included
+            let value = serde_json::to_string(&e.value).unwrap_or_default();
+            yaml.push_str(&format!("      {}: {}\n", e.key, value));
+        }
+    }
+    yaml
+}
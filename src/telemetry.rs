@@ -0,0 +1,168 @@
+use crate::tools;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+// Upper bounds of the latency histogram, in seconds.
+const BUCKETS: [f64; 10] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0];
+
+// Routes other than tools that get their own metrics label.
+const ROUTES: &[&str] = &[
+    "ping",
+    "tools",
+    "health",
+    "selftest",
+    "whoami",
+    "batch",
+    "pipeline",
+    "mcp",
+    "live",
+    "share",
+    "scaffold",
+    "openapi.json",
+    "docs",
+    "metrics",
+];
+
+// Keeps a caller-supplied ID (so traces join up across services) when it looks sane,
+// otherwise falls back to the Cloudflare ray ID or a fresh UUID.
+pub fn request_id(incoming: Option<&str>, cf_ray: Option<&str>) -> String {
+    let valid = |id: &&str| {
+        !id.is_empty()
+            && id.len() <= 128
+            && id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "-_.:".contains(c))
+    };
+    incoming
+        .filter(valid)
+        .or_else(|| cf_ray.filter(valid))
+        .map(String::from)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+}
+
+// Metrics label for a path: a known route or tool name, so unmatched paths cannot blow up
+// the number of series.
+pub fn route_label(path: &str) -> &str {
+    if !path.starts_with("/api/") {
+        return match path {
+            "/" => "home",
+            _ if path.starts_with("/s/") => "share-link",
+            _ => "other",
+        };
+    }
+    let route = path.trim_start_matches("/api/");
+    let route = route.split('/').next().unwrap_or(route);
+    if ROUTES.contains(&route) || tools::find(route).is_some() {
+        route
+    } else {
+        "other"
+    }
+}
+
+// One JSON object per request, written with `console_log!`.
+#[derive(Serialize)]
+pub struct LogLine<'a> {
+    pub level: &'static str,
+    pub request_id: &'a str,
+    pub method: &'a str,
+    pub path: &'a str,
+    pub route: &'a str,
+    pub status: u16,
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country: Option<&'a str>,
+}
+
+impl LogLine<'_> {
+    pub fn level(status: u16) -> &'static str {
+        match status {
+            500..=599 => "error",
+            400..=499 => "warn",
+            _ => "info",
+        }
+    }
+}
+
+#[derive(Default)]
+struct Histogram {
+    // Non-cumulative counts per bucket; the last slot is +Inf
+    counts: [u64; BUCKETS.len() + 1],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, secs: f64) {
+        let i = BUCKETS
+            .iter()
+            .position(|&le| secs <= le)
+            .unwrap_or(BUCKETS.len());
+        self.counts[i] += 1;
+        self.sum += secs;
+        self.count += 1;
+    }
+}
+
+// Counters since the isolate started. Each isolate keeps its own and they restart with it,
+// which `rate()` and `increase()` already tolerate.
+#[derive(Default)]
+pub struct Metrics {
+    requests: BTreeMap<(String, u16), u64>,
+    latency: BTreeMap<String, Histogram>,
+}
+
+impl Metrics {
+    pub fn record(&mut self, route: &str, status: u16, latency_ms: u64) {
+        *self
+            .requests
+            .entry((route.to_string(), status))
+            .or_default() += 1;
+        self.latency
+            .entry(route.to_string())
+            .or_default()
+            .observe(latency_ms as f64 / 1000.0);
+    }
+
+    // Prometheus text exposition format, version 0.0.4.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        out.push_str("# HELP toolbox_requests_total Requests handled, by route and status.\n");
+        out.push_str("# TYPE toolbox_requests_total counter\n");
+        for ((route, status), count) in &self.requests {
+            let _ = writeln!(
+                out,
+                "toolbox_requests_total{{route=\"{}\",status=\"{}\"}} {}",
+                route, status, count
+            );
+        }
+
+        out.push_str("# HELP toolbox_request_duration_seconds Time to produce a response.\n");
+        out.push_str("# TYPE toolbox_request_duration_seconds histogram\n");
+        for (route, h) in &self.latency {
+            let mut cumulative = 0;
+            for (i, count) in h.counts.iter().enumerate() {
+                cumulative += count;
+                let le = BUCKETS
+                    .get(i)
+                    .map_or("+Inf".to_string(), |le| le.to_string());
+                let _ = writeln!(
+                    out,
+                    "toolbox_request_duration_seconds_bucket{{route=\"{}\",le=\"{}\"}} {}",
+                    route, le, cumulative
+                );
+            }
+            let _ = writeln!(
+                out,
+                "toolbox_request_duration_seconds_sum{{route=\"{}\"}} {}",
+                route, h.sum
+            );
+            let _ = writeln!(
+                out,
+                "toolbox_request_duration_seconds_count{{route=\"{}\"}} {}",
+                route, h.count
+            );
+        }
+        out
+    }
+}
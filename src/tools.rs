@@ -0,0 +1,1794 @@
+use crate::auth::Access;
+#[cfg(any(feature = "crypto", feature = "formats", feature = "text"))]
+use crate::converters;
+use crate::error::ToolError;
+#[cfg(any(feature = "generators", feature = "text"))]
+use crate::generators;
+use crate::i18n::{self, Locale, Message};
+use crate::limits::Limits;
+use crate::output;
+#[cfg(feature = "generators")]
+use crate::stream;
+#[cfg(feature = "generators")]
+use crate::stream::Rows;
+#[cfg(any(feature = "devops", feature = "network"))]
+use crate::system;
+use crate::toolset::ToolSet;
+// Every tool lives behind one of the group features
+#[cfg(any(
+    feature = "crypto",
+    feature = "network",
+    feature = "formats",
+    feature = "devops",
+    feature = "generators",
+    feature = "text"
+))]
+use crate::{models::*, utils};
+use schemars::{JsonSchema, Schema, SchemaGenerator};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+// Coarse grouping of tools, used as the OpenAPI tag.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Group {
+    Crypto,
+    Network,
+    Formats,
+    Devops,
+    Generators,
+    Text,
+}
+
+impl Group {
+    pub const ALL: [Group; 6] = [
+        Group::Crypto,
+        Group::Network,
+        Group::Formats,
+        Group::Devops,
+        Group::Generators,
+        Group::Text,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Group::Crypto => "crypto",
+            Group::Network => "network",
+            Group::Formats => "formats",
+            Group::Devops => "devops",
+            Group::Generators => "generators",
+            Group::Text => "text",
+        }
+    }
+}
+
+// A tool is one `/api/<NAME>` endpoint: a typed request, a typed response and a pure `run`.
+// Nothing here depends on the worker runtime, so tools can be listed and called natively.
+pub trait Tool {
+    const NAME: &'static str;
+    // One line for `/api/tools` and MCP `tools/list`, in English; the zh-CN ones are in i18n
+    const DESCRIPTION: &'static str;
+    const GROUP: Group;
+    type Request: DeserializeOwned + Serialize + JsonSchema;
+    type Response: Serialize + JsonSchema;
+    // Pipelines feed the previous step's output into the `PIPE_INPUT` request field and pass
+    // the `PIPE_OUTPUT` response field on. Steps can override both with `into` / `select`.
+    const PIPE_INPUT: Option<&'static str> = None;
+    const PIPE_OUTPUT: Option<&'static str> = None;
+    // Media type of the document a generator returns in its single-field response; `format=raw`
+    // (or asking for this type in Accept) serves the document itself.
+    const MEDIA_TYPE: Option<&'static str> = None;
+    // File name for `/api/<NAME>/download`; only tools that set it get a download route.
+    const FILENAME: Option<&'static str> = None;
+    // Generators that set this get `/api/<NAME>/stream`, which writes the rows of `rows` as
+    // NDJSON or CSV while they are generated.
+    const STREAMS: bool = false;
+
+    fn run(data: Self::Request) -> Result<Self::Response, ToolError>;
+
+    // Tools whose output contains prose for people override this to honour the caller's
+    // locale; `run` then stands for English.
+    fn run_in(data: Self::Request, _locale: Locale) -> Result<Self::Response, ToolError> {
+        Self::run(data)
+    }
+
+    // Caps on individual fields (counts, lengths) on top of the size limits every input gets.
+    fn check(_data: &Self::Request, _limits: &Limits) -> Result<(), ToolError> {
+        Ok(())
+    }
+
+    // Override when the name depends on the request, e.g. the kind of a k8s manifest.
+    fn filename(_data: &Self::Request) -> Option<String> {
+        Self::FILENAME.map(String::from)
+    }
+
+    // Output one row at a time for `/stream`. `check` does not run there; MAX_STREAM_COUNT
+    // caps the row count instead.
+    #[cfg(feature = "generators")]
+    fn rows(_data: Self::Request) -> Option<Rows> {
+        None
+    }
+
+    // Whether the response follows from the request alone, so it may be cached and revalidated
+    // with an ETag. Tools that draw random numbers or read the clock say no.
+    fn is_pure(_data: &Self::Request) -> bool {
+        true
+    }
+}
+
+// Where a request comes from: an already-parsed JSON value (batch, pipeline), a raw JSON body
+// or a URL query string (GET routes).
+#[derive(Clone)]
+pub enum Input {
+    Json(Value),
+    Body(String),
+    Query(String),
+}
+
+impl Input {
+    // Enforces the body size and string length limits before deserializing.
+    pub fn parse<T: DeserializeOwned>(self, limits: &Limits) -> Result<T, ToolError> {
+        let malformed = |e: &dyn std::fmt::Display| ToolError::MalformedBody(e.to_string());
+        match self {
+            Input::Json(value) => {
+                limits.strings(&value)?;
+                serde_json::from_value(value).map_err(|e| malformed(&e))
+            }
+            Input::Body(body) => {
+                limits.body(body.len())?;
+                let value: Value = serde_json::from_str(&body).map_err(|e| malformed(&e))?;
+                limits.strings(&value)?;
+                serde_json::from_value(value).map_err(|e| malformed(&e))
+            }
+            Input::Query(query) => {
+                limits.body(query.len())?;
+                let pairs: Vec<(String, String)> =
+                    serde_urlencoded::from_str(&query).map_err(|e| malformed(&e))?;
+                for (key, value) in &pairs {
+                    limits.string(key)?;
+                    limits.string(value)?;
+                }
+                // `format` and `lang` choose the response, not the request; they are only
+                // passed on to a tool that has a field of the same name
+                serde_urlencoded::from_str(&query).or_else(|e| {
+                    let own: Vec<_> = pairs
+                        .iter()
+                        .filter(|(key, _)| key != "format" && key != "lang")
+                        .collect();
+                    if own.len() == pairs.len() {
+                        return Err(malformed(&e));
+                    }
+                    let own = serde_urlencoded::to_string(own).map_err(|e| malformed(&e))?;
+                    serde_urlencoded::from_str(&own).map_err(|_| malformed(&e))
+                })
+            }
+        }
+    }
+}
+
+// Per-request settings a tool call runs under.
+#[derive(Default)]
+pub struct Context {
+    pub limits: Limits,
+    pub access: Access,
+    pub tools: ToolSet,
+    pub locale: Locale,
+}
+
+impl Context {
+    // A disabled tool answers like one that does not exist, also inside batches and pipelines.
+    fn enabled(&self, name: &str) -> Result<(), ToolError> {
+        if self.tools.allows(name) {
+            Ok(())
+        } else {
+            Err(ToolError::unknown_tool(name))
+        }
+    }
+}
+
+// A generated document served as a file.
+pub struct Download {
+    pub filename: String,
+    pub media_type: &'static str,
+    pub body: String,
+}
+
+// Object-safe view of `Tool` so the registry can hold every tool in one slice.
+pub trait DynTool: Sync {
+    fn name(&self) -> &'static str;
+    fn description(&self, locale: Locale) -> &'static str;
+    fn group(&self) -> Group;
+    fn pipe_input(&self) -> Option<&'static str>;
+    fn pipe_output(&self) -> Option<&'static str>;
+    fn media_type(&self) -> Option<&'static str>;
+    fn default_filename(&self) -> Option<&'static str>;
+    fn call(&self, input: Input, ctx: &Context) -> Result<Value, ToolError>;
+    // The parsed request, serialized again, when the call is pure: the same for a query string
+    // and a body, or with defaults left out or spelled out. `None` when the call is not pure
+    // or the input does not parse; the call then reports why.
+    fn pure_request(&self, input: &Input, limits: &Limits) -> Option<Value>;
+    fn download(&self, input: Input, ctx: &Context) -> Result<Download, ToolError>;
+    fn streams(&self) -> bool;
+    #[cfg(feature = "generators")]
+    fn stream(&self, input: Input, ctx: &Context) -> Result<Rows, ToolError>;
+    // `$ref`s into the generator's definitions, which end up under `components/schemas`.
+    fn request_schema(&self, gen: &mut SchemaGenerator) -> Schema;
+    fn response_schema(&self, gen: &mut SchemaGenerator) -> Schema;
+
+    fn path(&self) -> String {
+        format!("/api/{}", self.name())
+    }
+}
+
+impl<T: Tool + Sync> DynTool for T {
+    fn name(&self) -> &'static str {
+        T::NAME
+    }
+
+    fn description(&self, locale: Locale) -> &'static str {
+        i18n::describe(locale, T::NAME, T::DESCRIPTION)
+    }
+
+    fn group(&self) -> Group {
+        T::GROUP
+    }
+
+    fn pipe_input(&self) -> Option<&'static str> {
+        T::PIPE_INPUT
+    }
+
+    fn pipe_output(&self) -> Option<&'static str> {
+        T::PIPE_OUTPUT
+    }
+
+    fn media_type(&self) -> Option<&'static str> {
+        T::MEDIA_TYPE
+    }
+
+    fn default_filename(&self) -> Option<&'static str> {
+        T::FILENAME
+    }
+
+    fn call(&self, input: Input, ctx: &Context) -> Result<Value, ToolError> {
+        ctx.enabled(T::NAME)?;
+        ctx.access.check(T::GROUP)?;
+        let data: T::Request = input.parse(&ctx.limits)?;
+        T::check(&data, &ctx.limits)?;
+        serde_json::to_value(T::run_in(data, ctx.locale)?)
+            .map_err(|e| ToolError::Internal(e.to_string()))
+    }
+
+    fn pure_request(&self, input: &Input, limits: &Limits) -> Option<Value> {
+        let data: T::Request = input.clone().parse(limits).ok()?;
+        if !T::is_pure(&data) {
+            return None;
+        }
+        serde_json::to_value(data).ok()
+    }
+
+    fn download(&self, input: Input, ctx: &Context) -> Result<Download, ToolError> {
+        ctx.enabled(T::NAME)?;
+        ctx.access.check(T::GROUP)?;
+        let data: T::Request = input.parse(&ctx.limits)?;
+        T::check(&data, &ctx.limits)?;
+        let filename = T::filename(&data)
+            .ok_or_else(|| ToolError::NotFound(Message::new("error.no_download", &[&T::NAME])))?;
+        let output = serde_json::to_value(T::run_in(data, ctx.locale)?)
+            .map_err(|e| ToolError::Internal(e.to_string()))?;
+        let body = output::document(&output)
+            .ok_or_else(|| ToolError::Internal(format!("'{}' returned no document", T::NAME)))?;
+        Ok(Download {
+            filename,
+            media_type: T::MEDIA_TYPE.unwrap_or("application/octet-stream"),
+            body: body.to_string(),
+        })
+    }
+
+    fn streams(&self) -> bool {
+        T::STREAMS
+    }
+
+    #[cfg(feature = "generators")]
+    fn stream(&self, input: Input, ctx: &Context) -> Result<Rows, ToolError> {
+        ctx.enabled(T::NAME)?;
+        ctx.access.check(T::GROUP)?;
+        let data: T::Request = input.parse(&ctx.limits)?;
+        let rows = T::rows(data)
+            .ok_or_else(|| ToolError::NotFound(Message::new("error.no_stream", &[&T::NAME])))?;
+        ctx.limits.stream_count("count", rows.count)?;
+        Ok(rows)
+    }
+
+    fn request_schema(&self, gen: &mut SchemaGenerator) -> Schema {
+        gen.subschema_for::<T::Request>()
+    }
+
+    fn response_schema(&self, gen: &mut SchemaGenerator) -> Schema {
+        gen.subschema_for::<T::Response>()
+    }
+}
+
+// Each tool is compiled in with the cargo feature named after its group.
+static TOOLS: &[&dyn DynTool] = &[
+    #[cfg(feature = "formats")]
+    &SqlTool,
+    #[cfg(feature = "text")]
+    &DiffTool,
+    #[cfg(feature = "devops")]
+    &CronTool,
+    #[cfg(feature = "network")]
+    &SubnetTool,
+    #[cfg(feature = "crypto")]
+    &Md5Tool,
+    #[cfg(feature = "crypto")]
+    &TokenTool,
+    #[cfg(feature = "generators")]
+    &UuidTool,
+    #[cfg(feature = "formats")]
+    &DateTool,
+    #[cfg(feature = "formats")]
+    &ColorTool,
+    #[cfg(feature = "crypto")]
+    &Base64Tool,
+    #[cfg(feature = "crypto")]
+    &JsEncTool,
+    #[cfg(feature = "formats")]
+    &JsonTool,
+    #[cfg(feature = "network")]
+    &UrlTool,
+    #[cfg(feature = "crypto")]
+    &PasswordTool,
+    #[cfg(feature = "generators")]
+    &QrTool,
+    #[cfg(feature = "devops")]
+    &ChmodTool,
+    #[cfg(feature = "formats")]
+    &YamlToTomlTool,
+    #[cfg(feature = "formats")]
+    &TomlToYamlTool,
+    #[cfg(feature = "formats")]
+    &JsonToYamlTool,
+    #[cfg(feature = "crypto")]
+    &JwtTool,
+    #[cfg(feature = "text")]
+    &RegexTool,
+    #[cfg(feature = "text")]
+    &RegexGenTool,
+    #[cfg(feature = "text")]
+    &EscapeTool,
+    #[cfg(feature = "text")]
+    &CaseTool,
+    #[cfg(feature = "devops")]
+    &TarTool,
+    #[cfg(feature = "devops")]
+    &PsTool,
+    #[cfg(feature = "devops")]
+    &TcpdumpTool,
+    #[cfg(feature = "devops")]
+    &GitTool,
+    #[cfg(feature = "devops")]
+    &GitCmdTool,
+    #[cfg(feature = "devops")]
+    &StraceTool,
+    #[cfg(feature = "devops")]
+    &IostatTool,
+    #[cfg(feature = "devops")]
+    &NiceTool,
+    #[cfg(feature = "devops")]
+    &LsTool,
+    #[cfg(feature = "devops")]
+    &FirewallTool,
+    #[cfg(feature = "devops")]
+    &SystemctlTool,
+    #[cfg(feature = "devops")]
+    &FindTool,
+    #[cfg(feature = "devops")]
+    &DockerfileTool,
+    #[cfg(feature = "devops")]
+    &NginxTool,
+    #[cfg(feature = "generators")]
+    &LoremTool,
+    #[cfg(feature = "devops")]
+    &RsyncTool,
+    #[cfg(feature = "generators")]
+    &FakeUserTool,
+    #[cfg(feature = "formats")]
+    &UnitTool,
+    #[cfg(feature = "network")]
+    &CurlTool,
+    #[cfg(feature = "generators")]
+    &CreditCardTool,
+    #[cfg(feature = "devops")]
+    &AwkTool,
+    #[cfg(feature = "devops")]
+    &SedTool,
+    #[cfg(feature = "text")]
+    &RegexBuildTool,
+    #[cfg(feature = "devops")]
+    &K8sYamlTool,
+    #[cfg(feature = "devops")]
+    &K8sCmdTool,
+    #[cfg(feature = "devops")]
+    &AnsibleTool,
+    #[cfg(feature = "devops")]
+    &ComposeTool,
+];
+
+pub fn all() -> &'static [&'static dyn DynTool] {
+    TOOLS
+}
+
+pub fn find(name: &str) -> Option<&'static dyn DynTool> {
+    TOOLS.iter().copied().find(|t| t.name() == name)
+}
+
+// --- Text & Formats ---
+
+#[cfg(feature = "formats")]
+pub struct SqlTool;
+#[cfg(feature = "formats")]
+impl Tool for SqlTool {
+    const NAME: &'static str = "sql";
+    const DESCRIPTION: &'static str = "Format a SQL statement";
+    const GROUP: Group = Group::Formats;
+    type Request = SqlRequest;
+    type Response = GenericResponse;
+    const PIPE_INPUT: Option<&'static str> = Some("sql");
+    const MEDIA_TYPE: Option<&'static str> = Some("application/sql");
+    const FILENAME: Option<&'static str> = Some("query.sql");
+
+    fn run(data: SqlRequest) -> Result<GenericResponse, ToolError> {
+        Ok(GenericResponse {
+            result: utils::format_sql(&data.sql),
+        })
+    }
+}
+
+#[cfg(feature = "text")]
+pub struct DiffTool;
+#[cfg(feature = "text")]
+impl Tool for DiffTool {
+    const NAME: &'static str = "diff";
+    const DESCRIPTION: &'static str = "Line-by-line diff of two texts";
+    const GROUP: Group = Group::Text;
+    type Request = DiffRequest;
+    type Response = DiffResponse;
+
+    fn run(data: DiffRequest) -> Result<DiffResponse, ToolError> {
+        Ok(utils::compute_diff(&data.old, &data.new))
+    }
+}
+
+#[cfg(feature = "devops")]
+pub struct CronTool;
+#[cfg(feature = "devops")]
+impl Tool for CronTool {
+    const NAME: &'static str = "cron";
+    const DESCRIPTION: &'static str = "Validate a cron expression and list its next runs";
+    const GROUP: Group = Group::Devops;
+    type Request = CronRequest;
+    type Response = CronResponse;
+    const PIPE_INPUT: Option<&'static str> = Some("cron");
+
+    fn is_pure(data: &CronRequest) -> bool {
+        data.from.is_some()
+    }
+
+    fn run(data: CronRequest) -> Result<CronResponse, ToolError> {
+        utils::check_cron(&data.cron, data.from)
+    }
+}
+
+#[cfg(feature = "network")]
+pub struct SubnetTool;
+#[cfg(feature = "network")]
+impl Tool for SubnetTool {
+    const NAME: &'static str = "subnet";
+    const DESCRIPTION: &'static str = "Calculate network, broadcast and host range of a subnet";
+    const GROUP: Group = Group::Network;
+    const PIPE_INPUT: Option<&'static str> = Some("ip");
+    type Request = SubnetRequest;
+    type Response = SubnetResponse;
+
+    fn run(data: SubnetRequest) -> Result<SubnetResponse, ToolError> {
+        utils::calculate_subnet(&data.ip, data.cidr)
+    }
+}
+
+#[cfg(feature = "crypto")]
+pub struct Md5Tool;
+#[cfg(feature = "crypto")]
+impl Tool for Md5Tool {
+    const NAME: &'static str = "md5";
+    const DESCRIPTION: &'static str = "MD5 digest in 32 and 16 character forms";
+    const GROUP: Group = Group::Crypto;
+    type Request = Md5Request;
+    type Response = Md5Response;
+    const PIPE_INPUT: Option<&'static str> = Some("text");
+    const PIPE_OUTPUT: Option<&'static str> = Some("md5_32_lower");
+
+    fn run(data: Md5Request) -> Result<Md5Response, ToolError> {
+        Ok(utils::calculate_md5(&data.text))
+    }
+}
+
+#[cfg(feature = "crypto")]
+pub struct TokenTool;
+#[cfg(feature = "crypto")]
+impl Tool for TokenTool {
+    const NAME: &'static str = "token";
+    const DESCRIPTION: &'static str = "Random token from the selected character classes";
+    const GROUP: Group = Group::Crypto;
+    type Request = TokenRequest;
+    type Response = TokenResponse;
+
+    fn check(data: &TokenRequest, limits: &Limits) -> Result<(), ToolError> {
+        limits.length("length", data.length)
+    }
+
+    fn is_pure(data: &TokenRequest) -> bool {
+        data.seed.is_some()
+    }
+
+    fn run(data: TokenRequest) -> Result<TokenResponse, ToolError> {
+        Ok(TokenResponse {
+            token: utils::generate_token(
+                data.length,
+                data.uppercase,
+                data.lowercase,
+                data.numbers,
+                data.symbols,
+                data.seed,
+            )?,
+            seed: data.seed,
+        })
+    }
+}
+
+#[cfg(feature = "generators")]
+pub struct UuidTool;
+#[cfg(feature = "generators")]
+impl Tool for UuidTool {
+    const NAME: &'static str = "uuid";
+    const DESCRIPTION: &'static str = "Generate v4 UUIDs";
+    const GROUP: Group = Group::Generators;
+    type Request = UuidRequest;
+    type Response = UuidResponse;
+    const STREAMS: bool = true;
+
+    fn check(data: &UuidRequest, limits: &Limits) -> Result<(), ToolError> {
+        limits.count("count", data.count)
+    }
+
+    fn is_pure(data: &UuidRequest) -> bool {
+        data.seed.is_some()
+    }
+
+    fn rows(data: UuidRequest) -> Option<Rows> {
+        let count = data.count;
+        let config = UuidConfig {
+            count,
+            hyphens: data.hyphens,
+            uppercase: data.uppercase,
+        };
+        let uuids = utils::uuids(config, data.seed);
+        Some(stream::column("uuid", count, uuids).seeded(data.seed))
+    }
+
+    fn run(data: UuidRequest) -> Result<UuidResponse, ToolError> {
+        let config = UuidConfig {
+            count: data.count,
+            hyphens: data.hyphens,
+            uppercase: data.uppercase,
+        };
+        Ok(UuidResponse {
+            uuids: utils::generate_uuids(config, data.seed),
+            seed: data.seed,
+        })
+    }
+}
+
+#[cfg(feature = "formats")]
+pub struct DateTool;
+#[cfg(feature = "formats")]
+impl Tool for DateTool {
+    const NAME: &'static str = "date";
+    const DESCRIPTION: &'static str = "Convert between Unix timestamps and date strings";
+    const GROUP: Group = Group::Formats;
+    type Request = DateRequest;
+    type Response = DateResponse;
+    const PIPE_INPUT: Option<&'static str> = Some("input");
+    const PIPE_OUTPUT: Option<&'static str> = Some("iso_8601");
+
+    fn run(data: DateRequest) -> Result<DateResponse, ToolError> {
+        utils::parse_date(&data.input)
+    }
+}
+
+#[cfg(feature = "formats")]
+pub struct ColorTool;
+#[cfg(feature = "formats")]
+impl Tool for ColorTool {
+    const NAME: &'static str = "color";
+    const DESCRIPTION: &'static str = "Convert a CSS color to HEX, RGB, HSL and CMYK";
+    const GROUP: Group = Group::Formats;
+    type Request = ColorRequest;
+    type Response = ColorResponse;
+    const PIPE_INPUT: Option<&'static str> = Some("input");
+    const PIPE_OUTPUT: Option<&'static str> = Some("hex");
+
+    fn run(data: ColorRequest) -> Result<ColorResponse, ToolError> {
+        utils::convert_color(&data.input)
+    }
+}
+
+#[cfg(feature = "crypto")]
+pub struct Base64Tool;
+#[cfg(feature = "crypto")]
+impl Tool for Base64Tool {
+    const NAME: &'static str = "base64";
+    const DESCRIPTION: &'static str = "Base64 encode or decode text";
+    const GROUP: Group = Group::Crypto;
+    type Request = Base64Request;
+    type Response = GenericResponse;
+    const PIPE_INPUT: Option<&'static str> = Some("text");
+
+    fn run(data: Base64Request) -> Result<GenericResponse, ToolError> {
+        Ok(GenericResponse {
+            result: utils::process_base64(&data.text, &data.action)?,
+        })
+    }
+}
+
+#[cfg(feature = "crypto")]
+pub struct JsEncTool;
+#[cfg(feature = "crypto")]
+impl Tool for JsEncTool {
+    const NAME: &'static str = "js-enc";
+    const DESCRIPTION: &'static str = "Obfuscate JavaScript into a hex-escaped eval";
+    const GROUP: Group = Group::Crypto;
+    type Request = JsEncRequest;
+    type Response = GenericResponse;
+    const PIPE_INPUT: Option<&'static str> = Some("js");
+
+    fn run(data: JsEncRequest) -> Result<GenericResponse, ToolError> {
+        Ok(GenericResponse {
+            result: converters::obfuscate_js(&data.js),
+        })
+    }
+}
+
+#[cfg(feature = "formats")]
+pub struct JsonTool;
+#[cfg(feature = "formats")]
+impl Tool for JsonTool {
+    const NAME: &'static str = "json";
+    const DESCRIPTION: &'static str = "Pretty-print and minify JSON";
+    const GROUP: Group = Group::Formats;
+    type Request = JsonRequest;
+    type Response = JsonResponse;
+    const PIPE_INPUT: Option<&'static str> = Some("input");
+    const PIPE_OUTPUT: Option<&'static str> = Some("pretty");
+
+    fn run(data: JsonRequest) -> Result<JsonResponse, ToolError> {
+        let (pretty, minified) = utils::process_json(&data.input)?;
+        Ok(JsonResponse { pretty, minified })
+    }
+}
+
+#[cfg(feature = "network")]
+pub struct UrlTool;
+#[cfg(feature = "network")]
+impl Tool for UrlTool {
+    const NAME: &'static str = "url";
+    const DESCRIPTION: &'static str = "URL encode, decode and parse";
+    const GROUP: Group = Group::Network;
+    type Request = UrlRequest;
+    type Response = UrlResponse;
+    const PIPE_INPUT: Option<&'static str> = Some("input");
+
+    fn run(data: UrlRequest) -> Result<UrlResponse, ToolError> {
+        let (encoded, decoded, protocol, host, path, params) = utils::process_url(&data.input);
+        Ok(UrlResponse {
+            encoded,
+            decoded,
+            protocol,
+            host,
+            path,
+            params,
+        })
+    }
+}
+
+#[cfg(feature = "crypto")]
+pub struct PasswordTool;
+#[cfg(feature = "crypto")]
+impl Tool for PasswordTool {
+    const NAME: &'static str = "password";
+    const DESCRIPTION: &'static str = "Generate a strong password";
+    const GROUP: Group = Group::Crypto;
+    type Request = PasswordRequest;
+    type Response = PasswordResponse;
+
+    fn check(data: &PasswordRequest, limits: &Limits) -> Result<(), ToolError> {
+        limits.length("length", data.length)
+    }
+
+    fn is_pure(_data: &PasswordRequest) -> bool {
+        false
+    }
+
+    fn run(data: PasswordRequest) -> Result<PasswordResponse, ToolError> {
+        Ok(PasswordResponse {
+            password: utils::generate_password_strong(
+                data.length,
+                data.uppercase,
+                data.lowercase,
+                data.numbers,
+                data.symbols,
+            )?,
+        })
+    }
+}
+
+#[cfg(feature = "generators")]
+pub struct QrTool;
+#[cfg(feature = "generators")]
+impl Tool for QrTool {
+    const NAME: &'static str = "qrcode";
+    const DESCRIPTION: &'static str = "Render text as a QR code SVG";
+    const GROUP: Group = Group::Generators;
+    type Request = QrRequest;
+    type Response = QrResponse;
+    const PIPE_INPUT: Option<&'static str> = Some("text");
+    const MEDIA_TYPE: Option<&'static str> = Some("image/svg+xml");
+    const FILENAME: Option<&'static str> = Some("qrcode.svg");
+
+    fn run(data: QrRequest) -> Result<QrResponse, ToolError> {
+        Ok(QrResponse {
+            svg: utils::generate_qr(&data.text)?,
+        })
+    }
+}
+
+#[cfg(feature = "devops")]
+pub struct ChmodTool;
+#[cfg(feature = "devops")]
+impl Tool for ChmodTool {
+    const NAME: &'static str = "chmod";
+    const DESCRIPTION: &'static str = "Build a chmod command from an octal mode";
+    const GROUP: Group = Group::Devops;
+    type Request = ChmodRequest;
+    type Response = ChmodResponse;
+    const PIPE_INPUT: Option<&'static str> = Some("octal");
+
+    fn run(data: ChmodRequest) -> Result<ChmodResponse, ToolError> {
+        system::calculate_chmod(&data.octal, &data.file)
+    }
+}
+
+#[cfg(feature = "formats")]
+pub struct YamlToTomlTool;
+#[cfg(feature = "formats")]
+impl Tool for YamlToTomlTool {
+    const NAME: &'static str = "yaml-to-toml";
+    const DESCRIPTION: &'static str = "Convert YAML to TOML";
+    const GROUP: Group = Group::Formats;
+    type Request = YamlRequest;
+    type Response = GenericResponse;
+    const PIPE_INPUT: Option<&'static str> = Some("yaml");
+    const MEDIA_TYPE: Option<&'static str> = Some("application/toml");
+    const FILENAME: Option<&'static str> = Some("config.toml");
+
+    fn run(data: YamlRequest) -> Result<GenericResponse, ToolError> {
+        Ok(GenericResponse {
+            result: utils::yaml_to_toml(&data.yaml)?,
+        })
+    }
+}
+
+#[cfg(feature = "formats")]
+pub struct TomlToYamlTool;
+#[cfg(feature = "formats")]
+impl Tool for TomlToYamlTool {
+    const NAME: &'static str = "toml-to-yaml";
+    const DESCRIPTION: &'static str = "Convert TOML to YAML";
+    const GROUP: Group = Group::Formats;
+    type Request = TomlRequest;
+    type Response = GenericResponse;
+    const PIPE_INPUT: Option<&'static str> = Some("toml");
+    const MEDIA_TYPE: Option<&'static str> = Some("application/yaml");
+    const FILENAME: Option<&'static str> = Some("config.yaml");
+
+    fn run(data: TomlRequest) -> Result<GenericResponse, ToolError> {
+        Ok(GenericResponse {
+            result: utils::toml_to_yaml(&data.toml)?,
+        })
+    }
+}
+
+#[cfg(feature = "formats")]
+pub struct JsonToYamlTool;
+#[cfg(feature = "formats")]
+impl Tool for JsonToYamlTool {
+    const NAME: &'static str = "json-to-yaml";
+    const DESCRIPTION: &'static str = "Convert JSON to YAML";
+    const GROUP: Group = Group::Formats;
+    type Request = JsonRequest;
+    type Response = GenericResponse;
+    const PIPE_INPUT: Option<&'static str> = Some("input");
+    const MEDIA_TYPE: Option<&'static str> = Some("application/yaml");
+    const FILENAME: Option<&'static str> = Some("config.yaml");
+
+    fn run(data: JsonRequest) -> Result<GenericResponse, ToolError> {
+        Ok(GenericResponse {
+            result: utils::json_to_yaml(&data.input)?,
+        })
+    }
+}
+
+#[cfg(feature = "crypto")]
+pub struct JwtTool;
+#[cfg(feature = "crypto")]
+impl Tool for JwtTool {
+    const NAME: &'static str = "jwt";
+    const DESCRIPTION: &'static str = "Decode the header and payload of a JWT";
+    const GROUP: Group = Group::Crypto;
+    type Request = JwtRequest;
+    type Response = JwtResponse;
+    const PIPE_INPUT: Option<&'static str> = Some("token");
+    const PIPE_OUTPUT: Option<&'static str> = Some("payload");
+
+    fn run(data: JwtRequest) -> Result<JwtResponse, ToolError> {
+        utils::parse_jwt(&data.token)
+    }
+}
+
+#[cfg(feature = "text")]
+pub struct RegexTool;
+#[cfg(feature = "text")]
+impl Tool for RegexTool {
+    const NAME: &'static str = "regex";
+    const DESCRIPTION: &'static str = "Find all matches of a regular expression";
+    const GROUP: Group = Group::Text;
+    type Request = RegexRequest;
+    type Response = RegexResponse;
+    const PIPE_INPUT: Option<&'static str> = Some("text");
+
+    fn run(data: RegexRequest) -> Result<RegexResponse, ToolError> {
+        utils::test_regex(&data.pattern, &data.text)
+    }
+}
+
+#[cfg(feature = "text")]
+pub struct RegexGenTool;
+#[cfg(feature = "text")]
+impl Tool for RegexGenTool {
+    const NAME: &'static str = "regex-gen";
+    const DESCRIPTION: &'static str = "Look up a common regular expression by key";
+    const GROUP: Group = Group::Text;
+    type Request = RegexGenRequest;
+    type Response = RegexPatternResponse;
+    const PIPE_INPUT: Option<&'static str> = Some("key");
+
+    fn run(data: RegexGenRequest) -> Result<RegexPatternResponse, ToolError> {
+        let pattern = utils::get_common_regex(&data.key)
+            .ok_or_else(|| ToolError::invalid("key", "regex.unknown_template", &[&data.key]))?;
+        Ok(RegexPatternResponse {
+            pattern: pattern.to_string(),
+        })
+    }
+}
+
+#[cfg(feature = "text")]
+pub struct EscapeTool;
+#[cfg(feature = "text")]
+impl Tool for EscapeTool {
+    const NAME: &'static str = "escape";
+    const DESCRIPTION: &'static str = "Escape or unescape HTML and JSON strings";
+    const GROUP: Group = Group::Text;
+    type Request = EscapeRequest;
+    type Response = GenericResponse;
+    const PIPE_INPUT: Option<&'static str> = Some("text");
+
+    fn run(data: EscapeRequest) -> Result<GenericResponse, ToolError> {
+        Ok(GenericResponse {
+            result: utils::process_escape(&data.text, &data.mode)?,
+        })
+    }
+}
+
+#[cfg(feature = "text")]
+pub struct CaseTool;
+#[cfg(feature = "text")]
+impl Tool for CaseTool {
+    const NAME: &'static str = "case";
+    const DESCRIPTION: &'static str = "Convert identifiers between naming conventions";
+    const GROUP: Group = Group::Text;
+    type Request = CaseRequest;
+    type Response = GenericResponse;
+    const PIPE_INPUT: Option<&'static str> = Some("text");
+
+    fn run(data: CaseRequest) -> Result<GenericResponse, ToolError> {
+        Ok(GenericResponse {
+            result: converters::convert_case(&data.text, &data.mode)?,
+        })
+    }
+}
+
+// --- DevOps Commands ---
+
+#[cfg(feature = "devops")]
+pub struct TarTool;
+#[cfg(feature = "devops")]
+impl Tool for TarTool {
+    const NAME: &'static str = "tar";
+    const DESCRIPTION: &'static str = "Build a tar command";
+    const GROUP: Group = Group::Devops;
+    type Request = TarRequest;
+    type Response = TarResponse;
+
+    fn run(data: TarRequest) -> Result<TarResponse, ToolError> {
+        Ok(system::generate_tar(
+            &data.op,
+            &data.comp,
+            data.verbose,
+            &data.archive,
+            &data.files,
+        ))
+    }
+}
+
+#[cfg(feature = "devops")]
+pub struct PsTool;
+#[cfg(feature = "devops")]
+impl Tool for PsTool {
+    const NAME: &'static str = "ps";
+    const DESCRIPTION: &'static str = "Build a ps command";
+    const GROUP: Group = Group::Devops;
+    type Request = PsRequest;
+    type Response = PsResponse;
+
+    fn run(data: PsRequest) -> Result<PsResponse, ToolError> {
+        Ok(system::generate_ps(
+            &data.format,
+            &data.sort,
+            data.tree,
+            &data.filter,
+            data.wide,
+            data.threads,
+            &data.user,
+            &data.pid,
+        ))
+    }
+}
+
+#[cfg(feature = "devops")]
+pub struct TcpdumpTool;
+#[cfg(feature = "devops")]
+impl Tool for TcpdumpTool {
+    const NAME: &'static str = "tcpdump";
+    const DESCRIPTION: &'static str = "Build a tcpdump command";
+    const GROUP: Group = Group::Devops;
+    type Request = TcpdumpRequest;
+    type Response = TcpdumpResponse;
+
+    fn run(data: TcpdumpRequest) -> Result<TcpdumpResponse, ToolError> {
+        Ok(system::generate_tcpdump(
+            &data.interface,
+            &data.protocol,
+            &data.host,
+            &data.port,
+            data.verbose,
+            data.ascii,
+            data.hex,
+            &data.write_file,
+            &data.count,
+        ))
+    }
+}
+
+#[cfg(feature = "devops")]
+pub struct GitTool;
+#[cfg(feature = "devops")]
+impl Tool for GitTool {
+    const NAME: &'static str = "git";
+    const DESCRIPTION: &'static str = "Build a git command";
+    const GROUP: Group = Group::Devops;
+    type Request = GitRequest;
+    type Response = GitResponse;
+
+    fn run(data: GitRequest) -> Result<GitResponse, ToolError> {
+        Ok(system::generate_git(
+            &data.cmd,
+            &data.target,
+            &data.msg,
+            &data.remote,
+            &data.branch,
+            data.opt_force,
+            data.opt_rebase,
+            data.opt_all,
+            data.opt_amend,
+            data.opt_hard,
+            data.opt_new_branch,
+            data.opt_tags,
+            data.opt_oneline,
+            data.opt_graph,
+        ))
+    }
+}
+
+#[cfg(feature = "devops")]
+pub struct GitCmdTool;
+#[cfg(feature = "devops")]
+impl Tool for GitCmdTool {
+    const NAME: &'static str = "git-cmd";
+    const DESCRIPTION: &'static str = "Common git recipes with an explanation";
+    const GROUP: Group = Group::Devops;
+    type Request = GitCmdRequest;
+    type Response = GitCmdResponse;
+
+    fn run(data: GitCmdRequest) -> Result<GitCmdResponse, ToolError> {
+        Self::run_in(data, Locale::En)
+    }
+
+    fn run_in(data: GitCmdRequest, locale: Locale) -> Result<GitCmdResponse, ToolError> {
+        Ok(system::generate_git_cmd(
+            &data.action,
+            &data.tag,
+            &data.msg,
+            &data.branch,
+            locale,
+        ))
+    }
+}
+
+#[cfg(feature = "devops")]
+pub struct StraceTool;
+#[cfg(feature = "devops")]
+impl Tool for StraceTool {
+    const NAME: &'static str = "strace";
+    const DESCRIPTION: &'static str = "Build an strace command";
+    const GROUP: Group = Group::Devops;
+    type Request = StraceRequest;
+    type Response = StraceResponse;
+
+    fn run(data: StraceRequest) -> Result<StraceResponse, ToolError> {
+        Ok(system::generate_strace(
+            &data.target,
+            data.is_pid,
+            data.follow,
+            data.summary,
+            &data.output_file,
+            &data.filter,
+            &data.string_limit,
+            data.timestamp,
+        ))
+    }
+}
+
+#[cfg(feature = "devops")]
+pub struct IostatTool;
+#[cfg(feature = "devops")]
+impl Tool for IostatTool {
+    const NAME: &'static str = "iostat";
+    const DESCRIPTION: &'static str = "Build an iostat command";
+    const GROUP: Group = Group::Devops;
+    type Request = IostatRequest;
+    type Response = IostatResponse;
+
+    fn run(data: IostatRequest) -> Result<IostatResponse, ToolError> {
+        Ok(system::generate_iostat(
+            &data.interval,
+            &data.count,
+            data.human,
+            data.extended,
+            &data.unit,
+            data.partitions,
+            data.timestamp,
+            &data.device,
+        ))
+    }
+}
+
+#[cfg(feature = "devops")]
+pub struct NiceTool;
+#[cfg(feature = "devops")]
+impl Tool for NiceTool {
+    const NAME: &'static str = "nice";
+    const DESCRIPTION: &'static str = "Build a nice or renice command";
+    const GROUP: Group = Group::Devops;
+    type Request = NiceRequest;
+    type Response = NiceResponse;
+
+    fn run(data: NiceRequest) -> Result<NiceResponse, ToolError> {
+        Ok(system::generate_nice(
+            &data.mode,
+            data.priority,
+            &data.command,
+            &data.target_type,
+            &data.target,
+        ))
+    }
+}
+
+#[cfg(feature = "devops")]
+pub struct LsTool;
+#[cfg(feature = "devops")]
+impl Tool for LsTool {
+    const NAME: &'static str = "ls";
+    const DESCRIPTION: &'static str = "Build an ls command";
+    const GROUP: Group = Group::Devops;
+    type Request = LsRequest;
+    type Response = LsResponse;
+
+    fn run(data: LsRequest) -> Result<LsResponse, ToolError> {
+        Ok(system::generate_ls(
+            &data.path,
+            data.all,
+            data.long,
+            data.human,
+            data.time,
+            data.reverse,
+            data.recursive,
+            data.inode,
+            data.directory,
+            data.color,
+        ))
+    }
+}
+
+#[cfg(feature = "devops")]
+pub struct FirewallTool;
+#[cfg(feature = "devops")]
+impl Tool for FirewallTool {
+    const NAME: &'static str = "firewall";
+    const DESCRIPTION: &'static str = "Build a firewall-cmd command";
+    const GROUP: Group = Group::Devops;
+    type Request = FirewallRequest;
+    type Response = FirewallResponse;
+
+    fn run(data: FirewallRequest) -> Result<FirewallResponse, ToolError> {
+        Ok(system::generate_firewall(
+            &data.op,
+            &data.zone,
+            &data.target_type,
+            &data.target,
+            data.permanent,
+        ))
+    }
+}
+
+#[cfg(feature = "devops")]
+pub struct SystemctlTool;
+#[cfg(feature = "devops")]
+impl Tool for SystemctlTool {
+    const NAME: &'static str = "systemctl";
+    const DESCRIPTION: &'static str = "Build a systemctl command";
+    const GROUP: Group = Group::Devops;
+    type Request = SystemctlRequest;
+    type Response = SystemctlResponse;
+
+    fn run(data: SystemctlRequest) -> Result<SystemctlResponse, ToolError> {
+        Ok(system::generate_systemctl(
+            &data.operation,
+            &data.service,
+            data.user_mode,
+            data.now,
+            data.force,
+            data.global,
+        ))
+    }
+}
+
+#[cfg(feature = "devops")]
+pub struct FindTool;
+#[cfg(feature = "devops")]
+impl Tool for FindTool {
+    const NAME: &'static str = "find";
+    const DESCRIPTION: &'static str = "Build a find command";
+    const GROUP: Group = Group::Devops;
+    type Request = FindRequest;
+    type Response = FindResponse;
+
+    fn run(data: FindRequest) -> Result<FindResponse, ToolError> {
+        Ok(system::generate_find(
+            &data.path,
+            &data.name,
+            data.iname,
+            &data.target_type,
+            &data.size,
+            &data.mtime,
+            data.empty,
+            &data.exec,
+        ))
+    }
+}
+
+#[cfg(feature = "devops")]
+pub struct RsyncTool;
+#[cfg(feature = "devops")]
+impl Tool for RsyncTool {
+    const NAME: &'static str = "rsync";
+    const DESCRIPTION: &'static str = "Build an rsync command and matching ssh config";
+    const GROUP: Group = Group::Devops;
+    type Request = RsyncRequest;
+    type Response = RsyncResponse;
+
+    fn run(data: RsyncRequest) -> Result<RsyncResponse, ToolError> {
+        Ok(system::generate_rsync(
+            &data.source,
+            &data.user,
+            &data.host,
+            &data.port,
+            &data.remote_path,
+            data.archive,
+            data.compress,
+            data.verbose,
+            data.delete,
+            data.dry_run,
+            data.progress,
+            data.ssh,
+            &data.exclude,
+        ))
+    }
+}
+
+#[cfg(feature = "network")]
+pub struct CurlTool;
+#[cfg(feature = "network")]
+impl Tool for CurlTool {
+    const NAME: &'static str = "curl";
+    const DESCRIPTION: &'static str = "Build a curl command and Python requests snippet";
+    const GROUP: Group = Group::Network;
+    type Request = CurlRequest;
+    type Response = CurlResponse;
+
+    fn run(data: CurlRequest) -> Result<CurlResponse, ToolError> {
+        Ok(system::generate_curl(
+            &data.method,
+            &data.url,
+            &data.headers,
+            &data.body,
+        ))
+    }
+}
+
+#[cfg(feature = "devops")]
+pub struct AwkTool;
+#[cfg(feature = "devops")]
+impl Tool for AwkTool {
+    const NAME: &'static str = "awk";
+    const DESCRIPTION: &'static str = "Build an awk command";
+    const GROUP: Group = Group::Devops;
+    type Request = AwkRequest;
+    type Response = AwkResponse;
+
+    fn run(data: AwkRequest) -> Result<AwkResponse, ToolError> {
+        Ok(system::generate_awk(
+            &data.separator,
+            &data.variable,
+            &data.code,
+            &data.file,
+        ))
+    }
+}
+
+#[cfg(feature = "devops")]
+pub struct SedTool;
+#[cfg(feature = "devops")]
+impl Tool for SedTool {
+    const NAME: &'static str = "sed";
+    const DESCRIPTION: &'static str = "Build a sed command";
+    const GROUP: Group = Group::Devops;
+    type Request = SedRequest;
+    type Response = SedResponse;
+
+    fn run(data: SedRequest) -> Result<SedResponse, ToolError> {
+        Ok(system::generate_sed(
+            &data.operation,
+            &data.pattern,
+            &data.replacement,
+            &data.flags,
+            data.inplace,
+            &data.file,
+        ))
+    }
+}
+
+#[cfg(feature = "devops")]
+pub struct K8sCmdTool;
+#[cfg(feature = "devops")]
+impl Tool for K8sCmdTool {
+    const NAME: &'static str = "k8s-cmd";
+    const DESCRIPTION: &'static str = "Common kubectl commands with an explanation";
+    const GROUP: Group = Group::Devops;
+    type Request = K8sCmdRequest;
+    type Response = K8sCmdResponse;
+
+    fn run(data: K8sCmdRequest) -> Result<K8sCmdResponse, ToolError> {
+        Self::run_in(data, Locale::En)
+    }
+
+    fn run_in(data: K8sCmdRequest, locale: Locale) -> Result<K8sCmdResponse, ToolError> {
+        let (command, description) = system::generate_k8s_cmd(&data, locale);
+        Ok(K8sCmdResponse {
+            command,
+            description,
+        })
+    }
+}
+
+// --- Config Generators ---
+
+#[cfg(feature = "devops")]
+pub struct DockerfileTool;
+#[cfg(feature = "devops")]
+impl Tool for DockerfileTool {
+    const NAME: &'static str = "dockerfile";
+    const DESCRIPTION: &'static str = "Generate a multi-stage Dockerfile";
+    const GROUP: Group = Group::Devops;
+    type Request = DockerfileRequest;
+    type Response = GenericResponse;
+    const MEDIA_TYPE: Option<&'static str> = Some("text/plain");
+    const FILENAME: Option<&'static str> = Some("Dockerfile");
+
+    fn run(data: DockerfileRequest) -> Result<GenericResponse, ToolError> {
+        Ok(GenericResponse {
+            result: system::generate_dockerfile(&data.stages),
+        })
+    }
+}
+
+#[cfg(feature = "devops")]
+pub struct NginxTool;
+#[cfg(feature = "devops")]
+impl Tool for NginxTool {
+    const NAME: &'static str = "nginx";
+    const DESCRIPTION: &'static str = "Generate an nginx server block";
+    const GROUP: Group = Group::Devops;
+    type Request = NginxRequest;
+    type Response = GenericResponse;
+    const MEDIA_TYPE: Option<&'static str> = Some("text/plain");
+    const FILENAME: Option<&'static str> = Some("nginx.conf");
+
+    fn run(data: NginxRequest) -> Result<GenericResponse, ToolError> {
+        Ok(GenericResponse {
+            result: utils::generate_nginx_config(
+                &data.domain,
+                data.port,
+                &data.root,
+                &data.locations,
+                &data.upstream,
+                data.https,
+                data.force_https,
+                &data.ssl_cert,
+                &data.ssl_key,
+                data.gzip,
+                &data.client_max_body_size,
+                &data.keepalive_timeout,
+                &data.proxy_connect_timeout,
+                &data.proxy_read_timeout,
+                &data.proxy_send_timeout,
+            ),
+        })
+    }
+}
+
+#[cfg(feature = "devops")]
+pub struct K8sYamlTool;
+#[cfg(feature = "devops")]
+impl Tool for K8sYamlTool {
+    const NAME: &'static str = "k8s-yaml";
+    const DESCRIPTION: &'static str = "Generate a Kubernetes manifest";
+    const GROUP: Group = Group::Devops;
+    type Request = K8sRequest;
+    type Response = GenericResponse;
+    const MEDIA_TYPE: Option<&'static str> = Some("application/yaml");
+    const FILENAME: Option<&'static str> = Some("deployment.yaml");
+
+    fn run(data: K8sRequest) -> Result<GenericResponse, ToolError> {
+        Ok(GenericResponse {
+            result: system::generate_k8s_yaml(&data)?,
+        })
+    }
+
+    fn filename(data: &K8sRequest) -> Option<String> {
+        Some(format!("{}.yaml", data.kind.to_lowercase()))
+    }
+}
+
+#[cfg(feature = "devops")]
+pub struct AnsibleTool;
+#[cfg(feature = "devops")]
+impl Tool for AnsibleTool {
+    const NAME: &'static str = "ansible";
+    const DESCRIPTION: &'static str = "Generate an Ansible playbook";
+    const GROUP: Group = Group::Devops;
+    type Request = AnsibleRequest;
+    type Response = GenericResponse;
+    const MEDIA_TYPE: Option<&'static str> = Some("application/yaml");
+    const FILENAME: Option<&'static str> = Some("playbook.yml");
+
+    fn run(data: AnsibleRequest) -> Result<GenericResponse, ToolError> {
+        Ok(GenericResponse {
+            result: system::generate_ansible_yaml(&data),
+        })
+    }
+}
+
+#[cfg(feature = "devops")]
+pub struct ComposeTool;
+#[cfg(feature = "devops")]
+impl Tool for ComposeTool {
+    const NAME: &'static str = "compose";
+    const DESCRIPTION: &'static str = "Generate a docker-compose.yml for one service";
+    const GROUP: Group = Group::Devops;
+    type Request = ComposeRequest;
+    type Response = GenericResponse;
+    const MEDIA_TYPE: Option<&'static str> = Some("application/yaml");
+    const FILENAME: Option<&'static str> = Some("docker-compose.yml");
+
+    fn run(data: ComposeRequest) -> Result<GenericResponse, ToolError> {
+        Ok(GenericResponse {
+            result: system::generate_compose(&data),
+        })
+    }
+}
+
+#[cfg(feature = "text")]
+pub struct RegexBuildTool;
+#[cfg(feature = "text")]
+impl Tool for RegexBuildTool {
+    const NAME: &'static str = "regex-build";
+    const DESCRIPTION: &'static str = "Build a regular expression from simple conditions";
+    const GROUP: Group = Group::Text;
+    type Request = RegexBuildRequest;
+    type Response = RegexBuildResponse;
+
+    fn run(data: RegexBuildRequest) -> Result<RegexBuildResponse, ToolError> {
+        Ok(generators::generate_custom_regex(
+            &data.starts_with,
+            &data.not_starts_with,
+            &data.ends_with,
+            &data.not_ends_with,
+            &data.contains,
+            &data.not_contains,
+        ))
+    }
+}
+
+// --- Generators & Converters ---
+
+#[cfg(feature = "generators")]
+pub struct LoremTool;
+#[cfg(feature = "generators")]
+impl Tool for LoremTool {
+    const NAME: &'static str = "lorem";
+    const DESCRIPTION: &'static str = "Lorem ipsum words, sentences or paragraphs";
+    const GROUP: Group = Group::Generators;
+    type Request = LoremRequest;
+    type Response = LoremResponse;
+    const STREAMS: bool = true;
+
+    fn check(data: &LoremRequest, limits: &Limits) -> Result<(), ToolError> {
+        limits.count("count", data.count)
+    }
+
+    fn is_pure(data: &LoremRequest) -> bool {
+        data.seed.is_some()
+    }
+
+    fn rows(data: LoremRequest) -> Option<Rows> {
+        let seed = utils::seed_or_random(data.seed);
+        let lorem = generators::lorem(&data.mode, utils::rng(Some(seed)));
+        Some(stream::column("text", data.count, lorem).seeded(Some(seed)))
+    }
+
+    fn run(data: LoremRequest) -> Result<LoremResponse, ToolError> {
+        let seed = utils::seed_or_random(data.seed);
+        Ok(LoremResponse {
+            result: generators::generate_lorem(data.count, &data.mode, utils::rng(Some(seed))),
+            seed,
+        })
+    }
+}
+
+#[cfg(feature = "generators")]
+pub struct FakeUserTool;
+#[cfg(feature = "generators")]
+impl Tool for FakeUserTool {
+    const NAME: &'static str = "fake-user";
+    const DESCRIPTION: &'static str = "Generate fake user identities";
+    const GROUP: Group = Group::Generators;
+    type Request = FakeUserRequest;
+    type Response = FakeUserResponse;
+    const STREAMS: bool = true;
+
+    fn check(data: &FakeUserRequest, limits: &Limits) -> Result<(), ToolError> {
+        limits.count("count", data.count)
+    }
+
+    fn is_pure(data: &FakeUserRequest) -> bool {
+        data.seed.is_some()
+    }
+
+    fn rows(data: FakeUserRequest) -> Option<Rows> {
+        let seed = utils::seed_or_random(data.seed);
+        let users = generators::fake_users(&data.locale, utils::rng(Some(seed)))
+            .map(|user| serde_json::json!(user));
+        Some(Rows::new(data.count, users).seeded(Some(seed)))
+    }
+
+    fn run(data: FakeUserRequest) -> Result<FakeUserResponse, ToolError> {
+        let seed = utils::seed_or_random(data.seed);
+        Ok(FakeUserResponse {
+            users: generators::generate_fake_users(
+                data.count,
+                &data.locale,
+                utils::rng(Some(seed)),
+            ),
+            seed,
+        })
+    }
+}
+
+#[cfg(feature = "generators")]
+pub struct CreditCardTool;
+#[cfg(feature = "generators")]
+impl Tool for CreditCardTool {
+    const NAME: &'static str = "credit-card";
+    const DESCRIPTION: &'static str = "Generate Luhn-valid test card numbers";
+    const GROUP: Group = Group::Generators;
+    type Request = CreditCardRequest;
+    type Response = CreditCardResponse;
+    const STREAMS: bool = true;
+
+    fn check(data: &CreditCardRequest, limits: &Limits) -> Result<(), ToolError> {
+        limits.count("count", data.count)
+    }
+
+    fn is_pure(data: &CreditCardRequest) -> bool {
+        data.seed.is_some()
+    }
+
+    fn rows(data: CreditCardRequest) -> Option<Rows> {
+        let seed = utils::seed_or_random(data.seed);
+        let cards = generators::credit_cards(&data.issuer, utils::rng(Some(seed)))
+            .map(|card| serde_json::json!(card));
+        Some(Rows::new(data.count, cards).seeded(Some(seed)))
+    }
+
+    fn run(data: CreditCardRequest) -> Result<CreditCardResponse, ToolError> {
+        let seed = utils::seed_or_random(data.seed);
+        Ok(CreditCardResponse {
+            cards: generators::generate_credit_cards(
+                data.count,
+                &data.issuer,
+                utils::rng(Some(seed)),
+            ),
+            seed,
+        })
+    }
+}
+
+#[cfg(feature = "formats")]
+pub struct UnitTool;
+#[cfg(feature = "formats")]
+impl Tool for UnitTool {
+    const NAME: &'static str = "unit-convert";
+    const DESCRIPTION: &'static str = "Convert storage and time units";
+    const GROUP: Group = Group::Formats;
+    type Request = UnitRequest;
+    type Response = UnitResponse;
+    const PIPE_INPUT: Option<&'static str> = Some("value");
+
+    fn run(data: UnitRequest) -> Result<UnitResponse, ToolError> {
+        let val = data
+            .value
+            .trim()
+            .parse::<f64>()
+            .map_err(|_| ToolError::invalid("value", "unit.not_a_number", &[&data.value]))?;
+        converters::convert_unit(val, &data.type_, &data.from, &data.to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn names_are_unique_and_routable() {
+        let mut seen = HashSet::new();
+        for tool in all() {
+            assert!(seen.insert(tool.name()), "duplicate tool '{}'", tool.name());
+            assert!(
+                tool.name()
+                    .bytes()
+                    .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-'),
+                "'{}' is not a plain path segment",
+                tool.name()
+            );
+            for locale in [Locale::En, Locale::ZhCn] {
+                assert!(
+                    !tool.description(locale).is_empty(),
+                    "'{}' has no description",
+                    tool.name()
+                );
+            }
+            assert_ne!(
+                tool.description(Locale::En),
+                tool.description(Locale::ZhCn),
+                "'{}' has no zh-CN description",
+                tool.name()
+            );
+        }
+    }
+
+    #[test]
+    fn find_returns_the_named_tool() {
+        for tool in all() {
+            assert_eq!(find(tool.name()).map(|t| t.name()), Some(tool.name()));
+        }
+        assert!(find("no-such-tool").is_none());
+        assert!(find("").is_none());
+    }
+
+    #[cfg(feature = "crypto")]
+    #[test]
+    fn call_parses_runs_and_reports_errors() {
+        let ctx = Context::default();
+        let md5 = find("md5").unwrap();
+        let output = md5.call(Input::Query("text=abc".into()), &ctx).unwrap();
+        assert_eq!(output["md5_32_lower"], "900150983cd24fb0d6963f7d28e17f72");
+        let err = md5.call(Input::Body("not json".into()), &ctx).unwrap_err();
+        assert_eq!(err.code(), "malformed_body");
+    }
+
+    #[cfg(feature = "crypto")]
+    #[test]
+    fn missing_and_unknown_fields_are_rejected() {
+        let ctx = Context::default();
+        let md5 = find("md5").unwrap();
+        for body in [r#"{"txt": "abc"}"#, "{}", r#"{"text": "abc", "extra": 1}"#] {
+            let err = md5.call(Input::Body(body.into()), &ctx).unwrap_err();
+            assert_eq!(err.status(), 400, "{}", body);
+        }
+        // Response options in the query string are not request fields
+        let query = Input::Query("text=abc&format=text&lang=zh-CN".into());
+        assert!(md5.call(query, &ctx).is_ok());
+        // Options keep their defaults
+        let token = find("token").unwrap();
+        assert!(token.call(Input::Json(serde_json::json!({})), &ctx).is_ok());
+    }
+
+    #[test]
+    fn disabled_tools_are_unknown() {
+        let Some(tool) = all().first() else { return };
+        let ctx = Context {
+            tools: ToolSet::new(None, Some(tool.name().to_string())),
+            ..Context::default()
+        };
+        let err = tool.call(Input::Body("{}".into()), &ctx).unwrap_err();
+        assert_eq!(err.code(), "not_found");
+    }
+
+    #[cfg(any(feature = "crypto", feature = "generators"))]
+    fn call_json(name: &str, request: Value) -> Value {
+        find(name)
+            .unwrap()
+            .call(Input::Json(request), &Context::default())
+            .unwrap()
+    }
+
+    #[cfg(feature = "generators")]
+    fn streamed(name: &str, request: Value) -> (Vec<Value>, Option<u64>) {
+        let rows = find(name)
+            .unwrap()
+            .stream(Input::Json(request), &Context::default())
+            .unwrap();
+        (rows.items.collect(), rows.seed)
+    }
+
+    #[cfg(feature = "crypto")]
+    #[test]
+    fn seeded_tokens_repeat_and_unseeded_ones_echo_nothing() {
+        let request = serde_json::json!({ "length": 24, "symbols": true, "seed": 9 });
+        let token = call_json("token", request.clone());
+        assert_eq!(token, call_json("token", request));
+        assert_eq!(token["seed"], 9);
+        assert_eq!(
+            utils::generate_token(8, true, true, true, false, Some(9)).unwrap(),
+            utils::generate_token(8, true, true, true, false, Some(9)).unwrap()
+        );
+        let unseeded = call_json("token", serde_json::json!({}));
+        assert!(unseeded.get("seed").is_none());
+    }
+
+    #[cfg(feature = "generators")]
+    #[test]
+    fn seeded_uuids_repeat_and_match_their_stream() {
+        let request = serde_json::json!({ "count": 5, "seed": 3 });
+        let uuids = call_json("uuid", request.clone());
+        assert_eq!(uuids, call_json("uuid", request.clone()));
+        let config = || UuidConfig {
+            count: 5,
+            hyphens: true,
+            uppercase: false,
+        };
+        assert_eq!(
+            uuids["uuids"],
+            serde_json::json!(utils::generate_uuids(config(), Some(3)))
+        );
+        assert_ne!(
+            utils::generate_uuids(config(), Some(3)),
+            utils::generate_uuids(config(), Some(4))
+        );
+
+        let (rows, seed) = streamed("uuid", request);
+        let column: Vec<&Value> = rows.iter().map(|row| &row["uuid"]).collect();
+        assert_eq!(serde_json::json!(column), uuids["uuids"]);
+        assert_eq!(seed, Some(3));
+        assert!(call_json("uuid", serde_json::json!({}))
+            .get("seed")
+            .is_none());
+    }
+
+    // Without a seed one is drawn and echoed; sending it back replays the run, streamed or not.
+    #[cfg(feature = "generators")]
+    #[test]
+    fn test_data_can_be_replayed_from_the_echoed_seed() {
+        for (name, field, request) in [
+            (
+                "fake-user",
+                "users",
+                serde_json::json!({ "count": 4, "locale": "cn" }),
+            ),
+            ("credit-card", "cards", serde_json::json!({ "count": 4 })),
+        ] {
+            let first = call_json(name, request.clone());
+            let seed = first["seed"].as_u64().unwrap();
+            assert!(seed < 1 << 53);
+            let mut replay = request.clone();
+            replay["seed"] = seed.into();
+            assert_eq!(call_json(name, replay.clone()), first, "{}", name);
+
+            let (rows, streamed_seed) = streamed(name, replay);
+            assert_eq!(Value::Array(rows), first[field], "{}", name);
+            assert_eq!(streamed_seed, Some(seed));
+        }
+
+        let first = call_json("lorem", serde_json::json!({ "count": 6, "mode": "words" }));
+        let replay = serde_json::json!({ "count": 6, "mode": "words", "seed": first["seed"] });
+        assert_eq!(call_json("lorem", replay.clone()), first);
+        let (rows, _) = streamed("lorem", replay);
+        let words: Vec<&str> = rows
+            .iter()
+            .map(|row| row["text"].as_str().unwrap())
+            .collect();
+        assert_eq!(words.join(" "), first["result"]);
+    }
+
+    #[cfg(feature = "generators")]
+    #[test]
+    fn generators_repeat_for_a_seed() {
+        let rng = || utils::rng(Some(11));
+        assert_eq!(
+            serde_json::json!(generators::generate_fake_users(3, "en", rng())),
+            serde_json::json!(generators::generate_fake_users(3, "en", rng()))
+        );
+        assert_eq!(
+            serde_json::json!(generators::generate_credit_cards(3, "visa", rng())),
+            serde_json::json!(generators::generate_credit_cards(3, "visa", rng()))
+        );
+        assert_eq!(
+            generators::generate_lorem(3, "sentences", rng()),
+            generators::generate_lorem(3, "sentences", rng())
+        );
+        assert_ne!(
+            generators::generate_lorem(8, "words", rng()),
+            generators::generate_lorem(8, "words", utils::rng(Some(12)))
+        );
+    }
+}
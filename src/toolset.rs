@@ -0,0 +1,46 @@
+use crate::tools::{self, DynTool};
+
+// Which tools a deployment serves, from the ENABLED_TOOLS and DISABLED_TOOLS vars
+// (comma-separated tool names, e.g. `credit-card,fake-user`). An empty or unset
+// ENABLED_TOOLS means every tool; DISABLED_TOOLS is then taken out of that. Unknown names
+// are ignored.
+#[derive(Clone, Debug, Default)]
+pub struct ToolSet {
+    enabled: Option<Vec<String>>,
+    disabled: Vec<String>,
+}
+
+impl ToolSet {
+    pub fn new(enabled: Option<String>, disabled: Option<String>) -> Self {
+        let names = |s: String| -> Vec<String> {
+            s.split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(String::from)
+                .collect()
+        };
+        ToolSet {
+            enabled: enabled.map(names).filter(|names| !names.is_empty()),
+            disabled: disabled.map(names).unwrap_or_default(),
+        }
+    }
+
+    pub fn allows(&self, name: &str) -> bool {
+        let enabled = match &self.enabled {
+            Some(names) => names.iter().any(|n| n == name),
+            None => true,
+        };
+        enabled && !self.disabled.iter().any(|n| n == name)
+    }
+
+    pub fn tools(&self) -> impl Iterator<Item = &'static dyn DynTool> + '_ {
+        tools::all()
+            .iter()
+            .copied()
+            .filter(move |t| self.allows(t.name()))
+    }
+
+    pub fn find(&self, name: &str) -> Option<&'static dyn DynTool> {
+        tools::find(name).filter(|t| self.allows(t.name()))
+    }
+}
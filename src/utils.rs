@@ -1,15 +1,31 @@
+use crate::error::ToolError;
 use crate::models::*;
+#[cfg(feature = "crypto")]
 use base64::{engine::general_purpose, Engine as _};
-use chrono::{TimeZone, Utc};
+#[cfg(any(feature = "devops", feature = "formats"))]
+use chrono::TimeZone;
+#[cfg(any(feature = "devops", feature = "formats"))]
+use chrono::Utc;
+#[cfg(feature = "devops")]
 use cron::Schedule;
+#[cfg(feature = "network")]
 use ipnetwork::IpNetwork;
+#[cfg(feature = "generators")]
 use qrcode::render::svg;
+#[cfg(feature = "generators")]
 use qrcode::QrCode;
-use rand::Rng;
+#[cfg(any(feature = "crypto", feature = "generators"))]
+use rand::{Rng, RngCore, SeedableRng};
+#[cfg(any(feature = "crypto", feature = "generators"))]
+use rand_chacha::ChaCha20Rng;
+#[cfg(feature = "formats")]
 use serde_json::Value;
+#[cfg(feature = "text")]
 use similar::{ChangeTag, TextDiff};
+#[cfg(feature = "devops")]
 use std::str::FromStr;
 
+#[cfg(feature = "formats")]
 pub fn format_sql(sql: &str) -> String {
     let options = sqlformat::FormatOptions {
         indent: sqlformat::Indent::Spaces(2),
@@ -19,6 +35,7 @@ pub fn format_sql(sql: &str) -> String {
     sqlformat::format(sql, &sqlformat::QueryParams::None, options)
 }
 
+#[cfg(feature = "text")]
 pub fn compute_diff(old: &str, new: &str) -> DiffResponse {
     let diff = TextDiff::from_lines(old, new);
     let mut chunks = Vec::new();
@@ -37,163 +54,149 @@ pub fn compute_diff(old: &str, new: &str) -> DiffResponse {
     DiffResponse { chunks }
 }
 
-pub fn check_cron(cron: &str) -> CronResponse {
+#[cfg(feature = "devops")]
+pub fn check_cron(cron: &str, from: Option<i64>) -> Result<CronResponse, ToolError> {
     // The `cron` crate requires 6 or 7 fields (Seconds is the first one).
     // Standard Linux cron has 5 fields. We need to handle this.
-    let cron_expr = if cron.trim().split_whitespace().count() == 5 {
+    let cron_expr = if cron.split_whitespace().count() == 5 {
         format!("0 {}", cron)
     } else {
         cron.to_string()
     };
-    match Schedule::from_str(&cron_expr) {
-        Ok(schedule) => {
-            let next_runs = schedule
-                .upcoming(Utc)
-                .take(5)
-                .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
-                .collect();
-            CronResponse {
-                valid: true,
-                next_runs,
-                error: String::new(),
-            }
-        }
-        Err(e) => CronResponse {
-            valid: false,
-            next_runs: vec![],
-            error: e.to_string(),
-        },
-    }
+    let schedule = Schedule::from_str(&cron_expr)
+        .map_err(|e| ToolError::invalid("cron", "cron.invalid", &[&e]))?;
+    let from = match from {
+        Some(secs) => Utc
+            .timestamp_opt(secs, 0)
+            .single()
+            .ok_or_else(|| ToolError::invalid("from", "date.invalid", &[]))?,
+        None => Utc::now(),
+    };
+    let next_runs = schedule
+        .after(&from)
+        .take(5)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+        .collect();
+    Ok(CronResponse { next_runs })
 }
 
-pub fn calculate_subnet(ip: &str, cidr: u8) -> SubnetResponse {
-    let net_str = format!("{}/{}", ip, cidr);
-    if let Ok(net) = net_str.parse::<IpNetwork>() {
-        let mask = net.mask();
-        let network = net.network();
-
-        let (broadcast, first_ip, last_ip, wildcard, binary_ip, binary_mask, ip_class, ip_type) =
-            match net {
-                IpNetwork::V4(v4_net) => {
-                    let broadcast = v4_net.broadcast();
-                    let mask_u32 = u32::from(v4_net.mask());
-                    let wildcard_u32 = !mask_u32;
-                    let wildcard = std::net::Ipv4Addr::from(wildcard_u32).to_string();
-
-                    let first = if v4_net.prefix() >= 31 {
-                        v4_net.network()
-                    } else {
-                        std::net::Ipv4Addr::from(u32::from(v4_net.network()) + 1)
-                    };
-                    let last = if v4_net.prefix() >= 31 {
-                        v4_net.broadcast()
-                    } else {
-                        std::net::Ipv4Addr::from(u32::from(v4_net.broadcast()) - 1)
-                    };
-
-                    let ip_u32 = u32::from(v4_net.ip());
-                    let bin_ip = format!(
-                        "{:08b}.{:08b}.{:08b}.{:08b}",
-                        ip_u32 >> 24,
-                        (ip_u32 >> 16) & 0xFF,
-                        (ip_u32 >> 8) & 0xFF,
-                        ip_u32 & 0xFF
-                    );
-                    let bin_mask = format!(
-                        "{:08b}.{:08b}.{:08b}.{:08b}",
-                        mask_u32 >> 24,
-                        (mask_u32 >> 16) & 0xFF,
-                        (mask_u32 >> 8) & 0xFF,
-                        mask_u32 & 0xFF
-                    );
-
-                    let first_octet = (ip_u32 >> 24) as u8;
-                    let class = if first_octet < 128 {
-                        "A"
-                    } else if first_octet < 192 {
-                        "B"
-                    } else if first_octet < 224 {
-                        "C"
-                    } else if first_octet < 240 {
-                        "D (Multicast)"
-                    } else {
-                        "E (Reserved)"
-                    };
-
-                    let type_ = if v4_net.ip().is_private() {
-                        "Private"
-                    } else {
-                        "Public"
-                    };
-
-                    (
-                        broadcast.to_string(),
-                        first.to_string(),
-                        last.to_string(),
-                        wildcard,
-                        bin_ip,
-                        bin_mask,
-                        class.to_string(),
-                        type_.to_string(),
-                    )
-                }
-                IpNetwork::V6(v6_net) => (
-                    "".to_string(),
-                    v6_net.network().to_string(),
-                    "".to_string(),
-                    "".to_string(),
-                    "".to_string(),
-                    "".to_string(),
-                    "IPv6".to_string(),
-                    "Public".to_string(),
-                ),
-            };
-
-        let total_hosts = if net.prefix() == 32 {
-            1
-        } else {
-            2u64.pow(32 - net.prefix() as u32)
+#[cfg(feature = "network")]
+pub fn calculate_subnet(ip: &str, cidr: u8) -> Result<SubnetResponse, ToolError> {
+    // `10.0.0.0/22` in `ip` carries its own prefix length
+    let net_str = if ip.contains('/') {
+        ip.to_string()
+    } else {
+        format!("{}/{}", ip, cidr)
+    };
+    let net = net_str
+        .parse::<IpNetwork>()
+        .map_err(|e| ToolError::invalid("ip", "subnet.invalid", &[&net_str, &e]))?;
+    let mask = net.mask();
+    let network = net.network();
+
+    let (broadcast, first_ip, last_ip, wildcard, binary_ip, binary_mask, ip_class, ip_type) =
+        match net {
+            IpNetwork::V4(v4_net) => {
+                let broadcast = v4_net.broadcast();
+                let mask_u32 = u32::from(v4_net.mask());
+                let wildcard_u32 = !mask_u32;
+                let wildcard = std::net::Ipv4Addr::from(wildcard_u32).to_string();
+
+                let first = if v4_net.prefix() >= 31 {
+                    v4_net.network()
+                } else {
+                    std::net::Ipv4Addr::from(u32::from(v4_net.network()) + 1)
+                };
+                let last = if v4_net.prefix() >= 31 {
+                    v4_net.broadcast()
+                } else {
+                    std::net::Ipv4Addr::from(u32::from(v4_net.broadcast()) - 1)
+                };
+
+                let ip_u32 = u32::from(v4_net.ip());
+                let bin_ip = format!(
+                    "{:08b}.{:08b}.{:08b}.{:08b}",
+                    ip_u32 >> 24,
+                    (ip_u32 >> 16) & 0xFF,
+                    (ip_u32 >> 8) & 0xFF,
+                    ip_u32 & 0xFF
+                );
+                let bin_mask = format!(
+                    "{:08b}.{:08b}.{:08b}.{:08b}",
+                    mask_u32 >> 24,
+                    (mask_u32 >> 16) & 0xFF,
+                    (mask_u32 >> 8) & 0xFF,
+                    mask_u32 & 0xFF
+                );
+
+                let first_octet = (ip_u32 >> 24) as u8;
+                let class = if first_octet < 128 {
+                    "A"
+                } else if first_octet < 192 {
+                    "B"
+                } else if first_octet < 224 {
+                    "C"
+                } else if first_octet < 240 {
+                    "D (Multicast)"
+                } else {
+                    "E (Reserved)"
+                };
+
+                let type_ = if v4_net.ip().is_private() {
+                    "Private"
+                } else {
+                    "Public"
+                };
+
+                (
+                    broadcast.to_string(),
+                    first.to_string(),
+                    last.to_string(),
+                    wildcard,
+                    bin_ip,
+                    bin_mask,
+                    class.to_string(),
+                    type_.to_string(),
+                )
+            }
+            IpNetwork::V6(v6_net) => (
+                "".to_string(),
+                v6_net.network().to_string(),
+                "".to_string(),
+                "".to_string(),
+                "".to_string(),
+                "".to_string(),
+                "IPv6".to_string(),
+                "Public".to_string(),
+            ),
         };
-        let usable_hosts = if total_hosts > 2 { total_hosts - 2 } else { 0 };
-
-        SubnetResponse {
-            valid: true,
-            ip: ip.to_string(),
-            cidr: cidr.to_string(),
-            mask: mask.to_string(),
-            wildcard,
-            network: network.to_string(),
-            broadcast,
-            first_ip,
-            last_ip,
-            total_hosts,
-            usable_hosts,
-            ip_class,
-            ip_type,
-            binary_ip,
-            binary_mask,
-        }
+
+    let total_hosts = if net.prefix() == 32 {
+        1
     } else {
-        SubnetResponse {
-            valid: false,
-            ip: ip.to_string(),
-            cidr: cidr.to_string(),
-            mask: "".into(),
-            wildcard: "".into(),
-            network: "".into(),
-            broadcast: "".into(),
-            first_ip: "".into(),
-            last_ip: "".into(),
-            total_hosts: 0,
-            usable_hosts: 0,
-            ip_class: "".into(),
-            ip_type: "".into(),
-            binary_ip: "".into(),
-            binary_mask: "".into(),
-        }
-    }
+        2u64.pow(32 - net.prefix() as u32)
+    };
+    let usable_hosts = total_hosts.saturating_sub(2);
+
+    Ok(SubnetResponse {
+        ip: net.ip().to_string(),
+        cidr: net.prefix().to_string(),
+        mask: mask.to_string(),
+        wildcard,
+        network: network.to_string(),
+        broadcast,
+        first_ip,
+        last_ip,
+        total_hosts,
+        usable_hosts,
+        ip_class,
+        ip_type,
+        binary_ip,
+        binary_mask,
+    })
 }
 
+#[cfg(feature = "crypto")]
 pub fn calculate_md5(text: &str) -> Md5Response {
     let digest = md5::compute(text);
     let hash = format!("{:x}", digest);
@@ -205,13 +208,15 @@ pub fn calculate_md5(text: &str) -> Md5Response {
     }
 }
 
+#[cfg(feature = "crypto")]
 pub fn generate_token(
     length: usize,
     uppercase: bool,
     lowercase: bool,
     numbers: bool,
     symbols: bool,
-) -> String {
+    seed: Option<u64>,
+) -> Result<String, ToolError> {
     let mut charset = String::new();
     if uppercase {
         charset.push_str("ABCDEFGHIJKLMNOPQRSTUVWXYZ");
@@ -227,36 +232,66 @@ pub fn generate_token(
     }
 
     if charset.is_empty() {
-        return String::new();
+        return Err(ToolError::invalid("uppercase", "token.no_charset", &[]));
     }
 
-    let mut rng = rand::thread_rng();
-    (0..length)
+    let mut rng = rng(seed);
+    Ok((0..length)
         .map(|_| {
             let idx = rng.gen_range(0..charset.len());
             charset.chars().nth(idx).unwrap()
         })
-        .collect()
+        .collect())
+}
+
+// A seed switches the random generators to ChaCha20, whose output for a seed is the same on
+// every platform and release. Without one they keep the full entropy of `thread_rng`.
+#[cfg(any(feature = "crypto", feature = "generators"))]
+pub fn rng(seed: Option<u64>) -> Box<dyn RngCore> {
+    match seed {
+        Some(seed) => Box::new(ChaCha20Rng::seed_from_u64(seed)),
+        None => Box::new(rand::thread_rng()),
+    }
 }
 
-pub fn generate_uuids(config: UuidConfig) -> Vec<String> {
-    let mut res = Vec::new();
-    for _ in 0..config.count {
-        let uuid = uuid::Uuid::new_v4();
-        let mut s = if config.hyphens {
+// Seed of a test-data generator (lorem, fake-user, credit-card): the given one, or a fresh
+// one that is echoed back so the run can be replayed. Fresh seeds stay below 2^53, which
+// JavaScript clients read back exactly.
+#[cfg(feature = "generators")]
+pub fn seed_or_random(seed: Option<u64>) -> u64 {
+    seed.unwrap_or_else(|| rand::thread_rng().gen::<u64>() >> 11)
+}
+
+#[cfg(feature = "generators")]
+pub fn generate_uuids(config: UuidConfig, seed: Option<u64>) -> Vec<String> {
+    uuids(config, seed).collect()
+}
+
+// `config.count` v4 UUIDs, generated as they are pulled. Unseeded ones come from
+// `Uuid::new_v4`, as before seeds existed.
+#[cfg(feature = "generators")]
+pub fn uuids(config: UuidConfig, seed: Option<u64>) -> impl Iterator<Item = String> {
+    let mut rng = seed.map(ChaCha20Rng::seed_from_u64);
+    (0..config.count).map(move |_| {
+        let uuid = match &mut rng {
+            Some(rng) => uuid::Builder::from_random_bytes(rng.gen()).into_uuid(),
+            None => uuid::Uuid::new_v4(),
+        };
+        let s = if config.hyphens {
             uuid.to_string()
         } else {
             uuid.simple().to_string()
         };
         if config.uppercase {
-            s = s.to_uppercase();
+            s.to_uppercase()
+        } else {
+            s
         }
-        res.push(s);
-    }
-    res
+    })
 }
 
-pub fn parse_date(input: &str) -> DateResponse {
+#[cfg(feature = "formats")]
+pub fn parse_date(input: &str) -> Result<DateResponse, ToolError> {
     let get_dt = || -> Option<chrono::DateTime<Utc>> {
         let trimmed_input = input.trim();
         if let Ok(ts) = trimmed_input.parse::<i64>() {
@@ -294,93 +329,81 @@ pub fn parse_date(input: &str) -> DateResponse {
         None
     };
 
-    if let Some(dt) = get_dt() {
-        DateResponse {
-            valid: true,
-            unix_sec: dt.timestamp(),
-            unix_milli: dt.timestamp_millis(),
-            iso_8601: dt.to_rfc3339(),
-            human_utc: dt.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
-            error: None,
-        }
-    } else {
-        DateResponse {
-            valid: false,
-            unix_sec: 0,
-            unix_milli: 0,
-            iso_8601: "".to_string(),
-            human_utc: "".to_string(),
-            error: Some("无效的日期格式或不存在的日期 (Invalid or non-existent date)".to_string()),
-        }
-    }
+    let dt = get_dt().ok_or_else(|| ToolError::invalid("input", "date.invalid", &[]))?;
+    Ok(DateResponse {
+        unix_sec: dt.timestamp(),
+        unix_milli: dt.timestamp_millis(),
+        iso_8601: dt.to_rfc3339(),
+        human_utc: dt.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+    })
 }
 
-pub fn convert_color(input: &str) -> ColorResponse {
-    match csscolorparser::parse(input) {
-        Ok(c) => {
-            let [r, g, b, _a] = c.to_rgba8();
-            let hex = c.to_hex_string();
-            let rgb = c.to_rgb_string();
-            let (h, s, l, _a) = c.to_hsla();
-            let hsl = format!("hsl({:.0}, {:.0}%, {:.0}%)", h, s * 100.0, l * 100.0);
-
-            let r_f = r as f64 / 255.0;
-            let g_f = g as f64 / 255.0;
-            let b_f = b as f64 / 255.0;
-            let k = 1.0 - r_f.max(g_f).max(b_f);
-            let cmyk = if k == 1.0 {
-                "cmyk(0%, 0%, 0%, 100%)".to_string()
-            } else {
-                let c_v = (1.0 - r_f - k) / (1.0 - k);
-                let m_v = (1.0 - g_f - k) / (1.0 - k);
-                let y_v = (1.0 - b_f - k) / (1.0 - k);
-                format!(
-                    "cmyk({:.0}%, {:.0}%, {:.0}%, {:.0}%)",
-                    c_v * 100.0,
-                    m_v * 100.0,
-                    y_v * 100.0,
-                    k * 100.0
-                )
-            };
+#[cfg(feature = "formats")]
+pub fn convert_color(input: &str) -> Result<ColorResponse, ToolError> {
+    let c = csscolorparser::parse(input)
+        .map_err(|e| ToolError::invalid("input", "color.invalid", &[&e]))?;
+    let [r, g, b, _a] = c.to_rgba8();
+    let hex = c.to_hex_string();
+    let rgb = c.to_rgb_string();
+    let (h, s, l, _a) = c.to_hsla();
+    let hsl = format!("hsl({:.0}, {:.0}%, {:.0}%)", h, s * 100.0, l * 100.0);
+
+    let r_f = r as f64 / 255.0;
+    let g_f = g as f64 / 255.0;
+    let b_f = b as f64 / 255.0;
+    let k = 1.0 - r_f.max(g_f).max(b_f);
+    let cmyk = if k == 1.0 {
+        "cmyk(0%, 0%, 0%, 100%)".to_string()
+    } else {
+        let c_v = (1.0 - r_f - k) / (1.0 - k);
+        let m_v = (1.0 - g_f - k) / (1.0 - k);
+        let y_v = (1.0 - b_f - k) / (1.0 - k);
+        format!(
+            "cmyk({:.0}%, {:.0}%, {:.0}%, {:.0}%)",
+            c_v * 100.0,
+            m_v * 100.0,
+            y_v * 100.0,
+            k * 100.0
+        )
+    };
 
-            ColorResponse {
-                valid: true,
-                hex,
-                rgb,
-                hsl,
-                cmyk,
-            }
-        }
-        Err(_) => ColorResponse {
-            valid: false,
-            hex: "".into(),
-            rgb: "".into(),
-            hsl: "".to_string(),
-            cmyk: "".into(),
-        },
-    }
+    Ok(ColorResponse {
+        hex,
+        rgb,
+        hsl,
+        cmyk,
+    })
 }
 
-pub fn process_base64(text: &str, action: &str) -> String {
-    if action == "encode" {
-        general_purpose::STANDARD.encode(text)
-    } else {
-        String::from_utf8(general_purpose::STANDARD.decode(text).unwrap_or_default())
-            .unwrap_or_default()
+#[cfg(feature = "crypto")]
+pub fn process_base64(text: &str, action: &str) -> Result<String, ToolError> {
+    match action {
+        "encode" => Ok(general_purpose::STANDARD.encode(text)),
+        "decode" => {
+            let bytes = general_purpose::STANDARD
+                .decode(text.trim())
+                .map_err(|e| ToolError::invalid("text", "base64.invalid", &[&e]))?;
+            String::from_utf8(bytes).map_err(|_| ToolError::invalid("text", "base64.not_utf8", &[]))
+        }
+        _ => Err(ToolError::invalid(
+            "action",
+            "base64.unknown_action",
+            &[&action],
+        )),
     }
 }
 
-pub fn process_json(input: &str) -> (String, String, Option<String>) {
-    match serde_json::from_str::<Value>(input) {
-        Ok(v) => (
-            serde_json::to_string_pretty(&v).unwrap_or_default(),
-            serde_json::to_string(&v).unwrap_or_default(),
-            None,
-        ),
-        Err(e) => (String::new(), String::new(), Some(e.to_string())),
-    }
+#[cfg(feature = "formats")]
+pub fn process_json(input: &str) -> Result<(String, String), ToolError> {
+    let v = serde_json::from_str::<Value>(input)
+        .map_err(|e| ToolError::invalid("input", "json.invalid", &[&e]))?;
+    Ok((
+        serde_json::to_string_pretty(&v).unwrap_or_default(),
+        serde_json::to_string(&v).unwrap_or_default(),
+    ))
 }
 
+#[cfg(feature = "network")]
 pub fn process_url(
     input: &str,
 ) -> (
@@ -411,99 +434,99 @@ pub fn process_url(
     (encoded, decoded, protocol, host, path, params)
 }
 
+#[cfg(feature = "crypto")]
 pub fn generate_password_strong(
     length: usize,
     uppercase: bool,
     lowercase: bool,
     numbers: bool,
     symbols: bool,
-) -> String {
-    generate_token(length, uppercase, lowercase, numbers, symbols)
+) -> Result<String, ToolError> {
+    generate_token(length, uppercase, lowercase, numbers, symbols, None)
 }
 
-pub fn generate_qr(text: &str) -> Result<String, String> {
-    let code = QrCode::new(text).map_err(|e| e.to_string())?;
+#[cfg(feature = "generators")]
+pub fn generate_qr(text: &str) -> Result<String, ToolError> {
+    let code =
+        QrCode::new(text).map_err(|e| ToolError::invalid("text", "qrcode.invalid", &[&e]))?;
     let svg = code.render::<svg::Color>().build();
     Ok(svg)
 }
 
-pub fn yaml_to_toml(yaml: &str) -> (String, String) {
-    match serde_yaml::from_str::<Value>(yaml) {
-        Ok(v) => match toml::to_string(&v) {
-            Ok(t) => (t, String::new()),
-            Err(e) => (String::new(), e.to_string()),
-        },
-        Err(e) => (String::new(), e.to_string()),
-    }
+#[cfg(feature = "formats")]
+pub fn yaml_to_toml(yaml: &str) -> Result<String, ToolError> {
+    let v = serde_yaml::from_str::<Value>(yaml)
+        .map_err(|e| ToolError::invalid("yaml", "yaml.invalid", &[&e]))?;
+    toml::to_string(&v).map_err(|e| ToolError::invalid("yaml", "convert.failed", &[&e]))
 }
 
-pub fn toml_to_yaml(toml: &str) -> (String, String) {
-    match toml::from_str::<Value>(toml) {
-        Ok(v) => match serde_yaml::to_string(&v) {
-            Ok(s) => (s, String::new()),
-            Err(e) => (String::new(), e.to_string()),
-        },
-        Err(e) => (String::new(), e.to_string()),
-    }
+#[cfg(feature = "formats")]
+pub fn toml_to_yaml(toml: &str) -> Result<String, ToolError> {
+    let v = toml::from_str::<Value>(toml)
+        .map_err(|e| ToolError::invalid("toml", "toml.invalid", &[&e]))?;
+    serde_yaml::to_string(&v).map_err(|e| ToolError::invalid("toml", "convert.failed", &[&e]))
+}
+
+#[cfg(feature = "formats")]
+pub fn json_to_yaml(input: &str) -> Result<String, ToolError> {
+    let v = serde_json::from_str::<Value>(input)
+        .map_err(|e| ToolError::invalid("input", "json.invalid", &[&e]))?;
+    serde_yaml::to_string(&v).map_err(|e| ToolError::invalid("input", "convert.failed", &[&e]))
 }
 
-pub fn parse_jwt(token: &str) -> JwtResponse {
-    let parts: Vec<&str> = token.split('.').collect();
+#[cfg(feature = "crypto")]
+pub fn parse_jwt(token: &str) -> Result<JwtResponse, ToolError> {
+    let parts: Vec<&str> = token.trim().split('.').collect();
     if parts.len() != 3 {
-        return JwtResponse {
-            error: Some("Invalid JWT format".into()),
-            header: "".into(),
-            payload: "".into(),
-        };
+        return Err(ToolError::invalid("token", "jwt.format", &[]));
     }
-    let decode = |s: &str| -> String {
+    let decode = |s: &str, part: &str| -> Result<String, ToolError> {
         let s = s.replace('-', "+").replace('_', "/");
         let padded = match s.len() % 4 {
             2 => format!("{}==", s),
             3 => format!("{}=", s),
             _ => s,
         };
-        String::from_utf8(general_purpose::STANDARD.decode(padded).unwrap_or_default())
-            .unwrap_or_default()
+        let bytes = general_purpose::STANDARD
+            .decode(padded)
+            .map_err(|e| ToolError::invalid("token", "jwt.part_invalid", &[&part, &e]))?;
+        String::from_utf8(bytes)
+            .map_err(|_| ToolError::invalid("token", "jwt.part_not_utf8", &[&part]))
     };
 
-    JwtResponse {
-        error: None,
-        header: decode(parts[0]),
-        payload: decode(parts[1]),
-    }
+    Ok(JwtResponse {
+        header: decode(parts[0], "header")?,
+        payload: decode(parts[1], "payload")?,
+    })
 }
 
-pub fn test_regex(pattern: &str, text: &str) -> RegexResponse {
-    match regex::RegexBuilder::new(pattern).multi_line(true).build() {
-        Ok(re) => {
-            let matches: Vec<String> = re.find_iter(text).map(|m| m.as_str().to_string()).collect();
-            RegexResponse {
-                count: matches.len(),
-                matches,
-                error: None,
-            }
-        }
-        Err(e) => RegexResponse {
-            matches: vec![],
-            count: 0,
-            error: Some(e.to_string()),
-        },
-    }
+#[cfg(feature = "text")]
+pub fn test_regex(pattern: &str, text: &str) -> Result<RegexResponse, ToolError> {
+    let re = regex::RegexBuilder::new(pattern)
+        .multi_line(true)
+        .build()
+        .map_err(|e| ToolError::invalid("pattern", "regex.invalid", &[&e]))?;
+    let matches: Vec<String> = re.find_iter(text).map(|m| m.as_str().to_string()).collect();
+    Ok(RegexResponse {
+        count: matches.len(),
+        matches,
+    })
 }
 
-pub fn get_common_regex(key: &str) -> &str {
+#[cfg(feature = "text")]
+pub fn get_common_regex(key: &str) -> Option<&'static str> {
     match key {
-        "email" => r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$",
-        "phone_cn" => r"^1[3-9]\d{9}$",
-        "ipv4" => r"^((25[0-5]|2[0-4]\d|[01]?\d\d?)\.){3}(25[0-5]|2[0-4]\d|[01]?\d\d?)$",
-        "url" => r"^https?://([\w-]+\.)+[\w-]+(/[\w-./?%&=]*)?$",
-        _ => "",
+        "email" => Some(r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$"),
+        "phone_cn" => Some(r"^1[3-9]\d{9}$"),
+        "ipv4" => Some(r"^((25[0-5]|2[0-4]\d|[01]?\d\d?)\.){3}(25[0-5]|2[0-4]\d|[01]?\d\d?)$"),
+        "url" => Some(r"^https?://([\w-]+\.)+[\w-]+(/[\w-./?%&=]*)?$"),
+        _ => None,
     }
 }
 
-pub fn process_escape(text: &str, mode: &str) -> String {
-    match mode {
+#[cfg(feature = "text")]
+pub fn process_escape(text: &str, mode: &str) -> Result<String, ToolError> {
+    Ok(match mode {
         "html_enc" => html_escape::encode_text(text).to_string(),
         "html_dec" => html_escape::decode_html_entities(text).to_string(),
         "json_enc" => text
@@ -518,10 +541,12 @@ pub fn process_escape(text: &str, mode: &str) -> String {
             .replace("\\n", "\n")
             .replace("\\r", "\r")
             .replace("\\t", "\t"),
-        _ => text.to_string(),
-    }
+        _ => return Err(ToolError::invalid("mode", "escape.unknown_mode", &[&mode])),
+    })
 }
 
+#[cfg(feature = "devops")]
+#[allow(clippy::too_many_arguments)]
 pub fn generate_nginx_config(
     domain: &str,
     port: u16,